@@ -0,0 +1,15 @@
+const { handler, index, remove } = require("./alias/handlers");
+const { twice, local } = require("./alias/esm");
+const handlers = require("./alias/handlers");
+
+function start() {
+  handler({});
+  index();
+  remove();
+  twice({});
+  local();
+}
+
+function viaNamespace() {
+  handlers.handler({});
+}
@@ -0,0 +1,7 @@
+const { double } = require("../mixed/baz");
+
+export const twice = double;
+
+export function local() {
+  pin = "local";
+}
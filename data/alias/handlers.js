@@ -0,0 +1,12 @@
+const { baz } = require("../mixed/baz");
+const users = require("./users");
+
+function list() {
+  pin = "list";
+}
+
+const handler = baz;
+const index = list;
+const remove = users.remove;
+
+module.exports = { handler, index, remove };
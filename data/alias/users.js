@@ -0,0 +1,5 @@
+function remove() {
+  pin = "remove";
+}
+
+module.exports = { remove };
@@ -0,0 +1,6 @@
+const { helper } = require("./util");
+
+function foo() {
+  helper();
+  helper();
+}
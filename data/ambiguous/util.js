@@ -0,0 +1,5 @@
+function helper() {
+  pin = "js";
+}
+
+module.exports = { helper };
@@ -0,0 +1,5 @@
+function helper() {
+  pin = "ts";
+}
+
+export { helper };
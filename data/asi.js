@@ -0,0 +1,10 @@
+function foo() {
+  pin = 1;
+}
+
+function main() {
+  let x
+  foo()
+  const { a } = require("./mixed/baz")
+  let y
+}
@@ -0,0 +1,22 @@
+function draw() {
+  pin = "module";
+}
+
+function foo() {
+  if (typeof window !== "undefined") {
+    function draw() {
+      pin = "block";
+    }
+    draw();
+  }
+  draw();
+}
+
+if (typeof window !== "undefined") {
+  function paint() {
+    pin = "paint";
+  }
+}
+
+paint();
+foo();
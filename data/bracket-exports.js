@@ -0,0 +1,8 @@
+const lib = require("./mixed/bracket-exports");
+
+function foo() {
+  lib.first();
+  lib.second();
+  lib.third();
+  lib.fourth();
+}
@@ -0,0 +1,12 @@
+const api = require("./chain/api");
+
+function start() {
+  api.users.fetch();
+  api.admin.remove();
+  api.status();
+}
+
+function unknown() {
+  api.users.missing();
+  api.missing.fetch();
+}
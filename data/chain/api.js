@@ -0,0 +1,8 @@
+const users = require("./users");
+const { admin } = require("./roles");
+
+function status() {
+  pin = "status";
+}
+
+module.exports = { users, admin, status };
@@ -0,0 +1,3 @@
+const admin = require("./admin");
+
+module.exports = { admin };
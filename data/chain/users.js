@@ -0,0 +1,5 @@
+function fetch() {
+  pin = "fetch";
+}
+
+module.exports = { fetch };
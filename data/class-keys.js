@@ -0,0 +1,39 @@
+class Keys {
+  "quoted"() {
+    pin = "quoted";
+  }
+
+  after() {
+    pin = "after";
+  }
+
+  [Symbol.iterator]() {
+    pin = "iterator";
+  }
+
+  ["computed"]() {
+    pin = "computed";
+  }
+
+  #secret() {
+    pin = "secret";
+  }
+
+  static #count = 0;
+
+  *items() {
+    pin = "items";
+  }
+
+  async *pages() {
+    pin = "pages";
+  }
+
+  [prefix + "Dynamic"]() {
+    pin = "dynamic";
+  }
+
+  42() {
+    pin = "answer";
+  }
+}
@@ -0,0 +1,29 @@
+function straight(a) {
+  return a + 1;
+}
+
+// if, for, && and ?: 5
+function branches(items, flag) {
+  if (flag && items.length) {
+    for (const item of items) {
+      item.ok = item.value ? "yes" : "no";
+    }
+  }
+}
+
+// while, ||, two cases and catch, not the ?. or ??: 6
+const handler = (req) => {
+  try {
+    while (req?.next || req.retry) {
+      switch (req.kind ?? "get") {
+        case "get":
+          break;
+        case "post":
+          break;
+      }
+    }
+  } catch (err) {
+    // if in a comment isn't counted
+    return "if";
+  }
+};
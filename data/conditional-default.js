@@ -0,0 +1,5 @@
+const { pick } = require("./esm/conditional");
+
+function start() {
+  pick();
+}
@@ -0,0 +1,9 @@
+const impl = require("./mixed/conditional");
+const blockImpl = require("./mixed/conditional-block");
+const ternaryImpl = require("./mixed/conditional-ternary");
+
+function foo() {
+  impl();
+  blockImpl();
+  ternaryImpl();
+}
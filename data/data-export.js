@@ -0,0 +1,7 @@
+const app = require("./mixed/config");
+
+function foo() {
+  app.routes.map(render);
+  app.config();
+  app.home();
+}
@@ -0,0 +1,16 @@
+function makeOpts() {
+  pin = "makeOpts";
+}
+
+function f(cb = () => {}, opts = makeOpts()) {
+  pin = "f";
+}
+
+const g = (cb = () => {}, opts = { a: 1 }) => {
+  pin = "g";
+};
+
+function foo() {
+  f();
+  g();
+}
@@ -0,0 +1,7 @@
+const { foo } = require("./mixed/babel");
+const babel = require("./mixed/babel");
+
+function main() {
+  foo();
+  babel.bar();
+}
@@ -0,0 +1,9 @@
+const _ = require("lodash");
+const { render } = require("@scope/ui/server");
+const util = require("./util");
+const fs = require("fs");
+const path = require("node:path");
+
+function lazy() {
+  return require("lodash").chunk();
+}
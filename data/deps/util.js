@@ -0,0 +1,3 @@
+const fp = require("lodash/fp");
+const React = require('react'), ui = require("@scope/ui");
+// require("commented-out")
@@ -0,0 +1,5 @@
+const { helper } = require(".");
+
+function foo() {
+  helper();
+}
@@ -0,0 +1,5 @@
+function helper() {
+  pin = "index";
+}
+
+module.exports = { helper };
@@ -0,0 +1,5 @@
+const dot = require("..");
+
+function foo() {
+  dot.helper();
+}
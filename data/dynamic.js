@@ -0,0 +1,10 @@
+function build(body) {
+  const fn = new Function("a", "b", body);
+  return fn;
+}
+
+const result = eval("1 + 2");
+// eval("commented out") and new Function("ignored")
+function run(code) {
+  return wrap(eval(code), vm.eval(code), "eval(string)");
+}
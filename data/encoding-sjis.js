@@ -0,0 +1,5 @@
+const { greet } = require("./mixed/legacy-sjis");
+
+function foo() {
+  greet();
+}
@@ -0,0 +1,5 @@
+const { greet } = require("./mixed/legacy-latin1");
+
+function foo() {
+  greet();
+}
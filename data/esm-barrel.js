@@ -0,0 +1,13 @@
+const { foo, default: main, qux, starred } = require("./esm/barrel");
+const barrel = require("./esm/barrel");
+
+function start() {
+  foo();
+  main();
+  qux();
+  starred();
+}
+
+function viaNamespace() {
+  barrel.qux();
+}
@@ -0,0 +1,9 @@
+import greet from "./esm-default/greet";
+import arrow from "./esm-default/arrow";
+import anonymous from "./esm-default/anonymous";
+
+function start() {
+  greet();
+  arrow();
+  anonymous();
+}
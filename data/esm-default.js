@@ -0,0 +1,7 @@
+import reducer from "./esm-default/reducers";
+import Profile from "./esm-default/profile";
+
+function start() {
+  reducer();
+  Profile();
+}
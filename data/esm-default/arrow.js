@@ -0,0 +1,3 @@
+export default async (name) => {
+  pin = "arrow";
+};
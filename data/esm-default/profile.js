@@ -0,0 +1,11 @@
+import { connect } from "react-redux";
+
+function mapState() {
+  pin = "mapState";
+}
+
+function Profile() {
+  pin = "Profile";
+}
+
+export default connect(mapState)(Profile);
@@ -0,0 +1,11 @@
+import { compose } from "redux";
+
+function users() {
+  pin = "users";
+}
+
+function posts() {
+  pin = "posts";
+}
+
+export default compose(users, posts);
@@ -0,0 +1,9 @@
+export function helper() {
+  pin = "helper";
+}
+
+export default class Widget {
+  render() {
+    pin = "render";
+  }
+}
@@ -0,0 +1,2 @@
+export { foo, bar as default, baz as qux } from "./m";
+export * from "./star";
@@ -0,0 +1 @@
+export { default as pick } from "../mixed/conditional-ternary";
@@ -0,0 +1,13 @@
+function foo() {
+  pin = "foo";
+}
+
+function bar() {
+  pin = "bar";
+}
+
+function baz() {
+  pin = "baz";
+}
+
+export { foo, bar, baz };
@@ -0,0 +1,5 @@
+function starred() {
+  pin = "starred";
+}
+
+export { starred };
@@ -0,0 +1,7 @@
+const { early, renamed, alias } = require("./hoisted/lib");
+
+function main() {
+  early();
+  renamed();
+  alias();
+}
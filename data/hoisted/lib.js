@@ -0,0 +1,10 @@
+export { early, late as renamed };
+export const alias = late;
+
+function early() {
+  pin = "early";
+}
+
+function late() {
+  pin = "late";
+}
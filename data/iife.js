@@ -0,0 +1,21 @@
+const ready = (async () => {
+  pin = "ready";
+  await connect();
+})();
+
+const legacy = (function () {
+  pin = "legacy";
+  return {};
+})();
+
+const wrapped = (() => {
+  pin = "wrapped";
+});
+
+function connect() {
+  pin = "connect";
+}
+
+function foo() {
+  pin = "foo";
+}
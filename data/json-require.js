@@ -0,0 +1,14 @@
+const settings = require("./mixed/settings.json");
+const defaults = require("./mixed/settings");
+const { routes } = require("./mixed/settings.json");
+
+function foo() {
+  pin = settings.name;
+  settings.load();
+  defaults.routes.map(bar);
+  bar();
+}
+
+function bar() {
+  pin = "bar";
+}
@@ -0,0 +1,41 @@
+function check() {
+  pin = "check";
+}
+
+function pick() {
+  pin = "pick";
+}
+
+function start() {
+  pin = "start";
+}
+
+function guard() {
+  pin = "guard";
+}
+
+// a helper named like the keyword of a macro, see --call-like.
+function unless() {
+  pin = "unless";
+}
+
+function finish() {
+  pin = "finish";
+}
+
+function foo() {
+  while (check()) {}
+  switch (pick()) {
+  }
+  for (start(); i < 1; i++) {}
+  try {
+  } catch (err) {}
+  unless (guard()) {}
+  return (finish());
+}
+
+function bar() {
+  return function (err) {
+    finish();
+  };
+}
@@ -0,0 +1,19 @@
+function foo() {
+  pin = "foo";
+}
+
+function impl() {
+  pin = "impl";
+}
+
+function baz() {
+  pin = "baz";
+}
+
+Object.assign(module.exports, {
+  foo,
+  bar: impl,
+  version: "1.0",
+});
+
+Object.assign(exports, { baz });
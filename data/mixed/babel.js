@@ -0,0 +1,18 @@
+"use strict";
+
+Object.defineProperty(exports, "__esModule", { value: true });
+Object.defineProperty(exports, "foo", {
+  enumerable: true,
+  get: function () {
+    return _foo;
+  },
+});
+Object.defineProperty(module.exports, "bar", { value: bar });
+
+function _foo() {
+  pin = "foo";
+}
+
+function bar() {
+  pin = "bar";
+}
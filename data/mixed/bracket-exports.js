@@ -0,0 +1,18 @@
+"use strict";
+exports['first'] = function () {
+  pin = "first";
+};
+module.exports["second"] = (obj) => {
+  pin = "second";
+};
+exports.third = function third() {
+  pin = "third";
+};
+exports[key] = function () {
+  pin = "computed";
+};
+exports.fourth = fourth;
+
+function fourth() {
+  pin = "fourth";
+}
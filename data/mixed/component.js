@@ -0,0 +1,9 @@
+function mapState(state) {
+  pin = "mapState";
+}
+
+function Component(props) {
+  pin = "Component";
+}
+
+module.exports = connect(mapState)(Component);
@@ -0,0 +1,13 @@
+const devImpl = () => {
+  pin = "block dev";
+};
+
+const prodImpl = () => {
+  pin = "block prod";
+};
+
+if (__DEV__) {
+  module.exports = devImpl;
+} else {
+  module.exports = prodImpl;
+}
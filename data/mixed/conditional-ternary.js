@@ -0,0 +1,9 @@
+function devImpl() {
+  pin = "ternary dev";
+}
+
+function prodImpl() {
+  pin = "ternary prod";
+}
+
+module.exports = __DEV__ ? devImpl : prodImpl;
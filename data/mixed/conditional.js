@@ -0,0 +1,10 @@
+function devImpl() {
+  pin = "dev";
+}
+
+function prodImpl() {
+  pin = "prod";
+}
+
+if (process.env.NODE_ENV !== "production") module.exports = devImpl;
+else module.exports = prodImpl;
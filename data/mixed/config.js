@@ -0,0 +1,10 @@
+module.exports = {
+  routes: [{ path: "/", handler: home }],
+  config: { debug: true, retries: (1 + 2) },
+  name: "app",
+  home,
+};
+
+function home() {
+  pin = "home";
+}
@@ -0,0 +1,15 @@
+const handler =
+  async (req, res) => {
+    pin = "handler";
+  };
+
+const wrapped =
+  (req) => {
+    pin = "wrapped";
+  };
+
+const direct = async () => {
+  pin = "direct";
+};
+
+module.exports = { handler, wrapped, direct };
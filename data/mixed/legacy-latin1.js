@@ -0,0 +1,5 @@
+function greet() {
+  pin = "caf";
+}
+
+module.exports = { greet };
@@ -0,0 +1,5 @@
+function greet() {
+  pin = "ɂ";
+}
+
+module.exports = { greet };
@@ -0,0 +1,5 @@
+function single() {
+  pin = "single";
+}
+
+module.exports = single;
@@ -0,0 +1,9 @@
+function reducerA(state) {
+  pin = "reducerA";
+}
+
+function reducerB(state) {
+  pin = "reducerB";
+}
+
+module.exports = compose(reducerA, reducerB);
@@ -0,0 +1,7 @@
+const { loud } = require("./esm");
+import cjs = require("./cjs");
+
+function start(): void {
+  loud("world");
+  cjs.parse("input");
+}
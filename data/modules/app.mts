@@ -0,0 +1,8 @@
+import { greet, loud } from "./esm.mjs";
+import * as cjs from "./cjs.cjs";
+
+function start(): void {
+  greet("world");
+  loud("world");
+  cjs.parse("input");
+}
@@ -0,0 +1,5 @@
+function parse(input: string): void {
+  pin = "parse";
+}
+
+module.exports = { parse };
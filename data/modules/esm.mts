@@ -0,0 +1,9 @@
+export function greet(name: string): void {
+  pin = "greet";
+}
+
+function shout(name: string): void {
+  pin = "shout";
+}
+
+export { shout as loud };
@@ -0,0 +1,10 @@
+// café ☕ — naïve comments before the first function
+const greeting = "héllo wörld 🌍";
+
+function greet(name = "José") {
+  pin = "¡hola " + name + "!";
+}
+
+const wave = (emoji = "👋") => {
+  pin = emoji;
+}; function après() { pin = "ü"; }
@@ -0,0 +1,13 @@
+function outer() {
+  function inner() {
+    const deepest = () => {
+      return;
+    };
+  }
+
+  if (true) {
+    function inBlock() {}
+  }
+}
+
+const sibling = () => {};
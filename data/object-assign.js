@@ -0,0 +1,8 @@
+const { foo, bar } = require("./mixed/assign");
+const assign = require("./mixed/assign");
+
+function main() {
+  foo();
+  bar();
+  assign.baz();
+}
@@ -0,0 +1,8 @@
+function load() {
+  pin = "load";
+  return {};
+}
+
+function main() {
+  Object.keys(load()).forEach(print);
+}
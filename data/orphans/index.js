@@ -0,0 +1,5 @@
+const a = require("./lib/a");
+
+function main() {
+  a.run();
+}
@@ -0,0 +1,7 @@
+import { helper } from "./b";
+
+function run() {
+  helper();
+}
+
+module.exports = { run };
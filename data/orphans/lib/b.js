@@ -0,0 +1,3 @@
+function helper() {}
+
+export { helper };
@@ -0,0 +1,3 @@
+const self = require("./unused");
+
+function unused() {}
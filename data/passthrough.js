@@ -0,0 +1,19 @@
+const { a, b } = require("./passthrough/subset");
+const subset = require("./passthrough/subset");
+const { first } = require("./passthrough/members");
+const members = require("./passthrough/members");
+
+function main() {
+  a();
+  b();
+}
+
+function viaNamespace() {
+  subset.a();
+  subset.b();
+}
+
+function viaMembers() {
+  first();
+  members.second();
+}
@@ -0,0 +1,4 @@
+const lib = require("./x");
+
+exports.first = lib.a;
+exports.second = lib.b;
@@ -0,0 +1,4 @@
+const { a } = require("./x");
+const { b: renamed } = require("./x");
+
+module.exports = { a, b: renamed };
@@ -0,0 +1,9 @@
+function a() {
+  pin = "a";
+}
+
+function b() {
+  pin = "b";
+}
+
+module.exports = { a, b };
@@ -0,0 +1,7 @@
+const { qux } = require("./mixed/index-barrel");
+const mixed = require("./mixed/barrel");
+
+function foo(obj) {
+  qux(obj);
+  mixed.xyz();
+}
@@ -0,0 +1,19 @@
+function countdown(n) {
+  pin = "countdown";
+  countdown(n - 1);
+}
+
+function isEven(n) {
+  pin = "even";
+  return isOdd(n - 1);
+}
+
+function isOdd(n) {
+  pin = "odd";
+  return isEven(n - 1);
+}
+
+function main() {
+  countdown(3);
+  isEven(4);
+}
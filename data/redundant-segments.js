@@ -0,0 +1,7 @@
+const { baz } = require("./missing/../mixed/./baz");
+const { double } = require("././mixed//../mixed/baz.js");
+
+function main() {
+  baz({});
+  double({});
+}
@@ -0,0 +1,11 @@
+const { parse } = require("./lib");
+
+function load(file) {
+  return parse(file);
+}
+
+function run(file) {
+  return load(file);
+}
+
+module.exports = { run };
@@ -0,0 +1,7 @@
+const { render } = require("./lib");
+
+function load() {
+  return render({});
+}
+
+module.exports = { load };
@@ -0,0 +1,5 @@
+function format(input) {
+  return input;
+}
+
+module.exports = { format };
@@ -0,0 +1,11 @@
+const { format } = require("./format");
+
+function parse(input) {
+  return format(input);
+}
+
+function render(tree) {
+  return tree;
+}
+
+module.exports = { parse, render };
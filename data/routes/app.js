@@ -0,0 +1,28 @@
+const express = require("express");
+const users = require("./users");
+
+const app = express();
+const router = express.Router();
+
+function getUsers(req, res) {
+  res.json([]);
+}
+
+function createUser(req, res) {
+  res.status(201).end();
+}
+
+router.get("/users", getUsers);
+router.post("/users", auth, createUser);
+router.delete("/users/:id", users.remove);
+app.put(
+  "/users/:id",
+  users.update,
+);
+
+// router.get("/commented", getUsers);
+router.get("/inline", (req, res) => res.end());
+router.use("/admin", adminRouter);
+app.get("port");
+
+module.exports = { app, router };
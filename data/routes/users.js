@@ -0,0 +1,9 @@
+function remove(req, res) {
+  res.end();
+}
+
+function update(req, res) {
+  res.end();
+}
+
+module.exports = { remove, update };
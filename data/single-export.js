@@ -0,0 +1,5 @@
+const single = require("./mixed/single-export");
+
+function foo() {
+  single();
+}
@@ -0,0 +1,7 @@
+const summary = require("./mixed/baz");
+
+function analyze(exports) {
+  return exports.pin;
+}
+
+analyze(summary);
@@ -0,0 +1,45 @@
+function foo(kind) {
+  switch (kind) {
+    case "a": {
+      function caseFn() {
+        pin = "case";
+      }
+      caseFn();
+      break;
+    }
+    default:
+      const defaultFn = () => {
+        pin = "default";
+      };
+      defaultFn();
+  }
+
+  try {
+    function tryFn() {
+      pin = "try";
+    }
+    tryFn();
+  } catch (err) {
+    const catchFn = () => {
+      pin = "catch";
+    };
+    catchFn();
+  } finally {
+    function finallyFn() {
+      pin = "finally";
+    }
+    finallyFn();
+  }
+
+  tryFn();
+}
+
+function bar() {
+  try {
+    function hidden() {
+      pin = "hidden";
+    }
+  } finally {
+  }
+  hidden();
+}
@@ -0,0 +1,5 @@
+import greet = require("./greeter");
+
+function foo(): void {
+  greet("world");
+}
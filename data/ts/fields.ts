@@ -0,0 +1,31 @@
+export class Form {
+  onClick = e => {
+    pin = "click";
+  };
+
+  onSubmit = async () => {
+    pin = "submit";
+  };
+
+  onReset = async event => {
+    pin = "reset";
+  };
+
+  validate = function () {
+    pin = "validate";
+  };
+
+  label = () => "form";
+
+  greet(name: string): void;
+  greet(name: string, greeting: string): void;
+  greet(name: string, greeting?: string): void {
+    pin = "greet";
+  }
+
+  abstract render(): void;
+
+  close() {
+    pin = "close";
+  }
+}
@@ -0,0 +1,5 @@
+function greet(name: string): void {
+  pin = "greet";
+}
+
+export = greet;
@@ -0,0 +1,27 @@
+import Service = require("./greeter");
+
+export class Greeter {
+  private count: number = 0;
+  static readonly label = "greeter";
+
+  constructor(private readonly svc: Service, public name: string = "world") {
+    pin = "constructor";
+  }
+
+  // greets with the configured name
+  public async greet(): Promise<void> {
+    pin = "greet";
+  }
+
+  handle = (event: { type: string }) => {
+    pin = "handle";
+  };
+
+  get size() {
+    return this.count;
+  }
+}
+
+function foo() {
+  pin = "foo";
+}
@@ -0,0 +1,7 @@
+const { Button, Slider, Dialog } = require("./widgets");
+
+function render() {
+  Button();
+  Slider();
+  Dialog();
+}
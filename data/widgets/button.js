@@ -0,0 +1,5 @@
+function Button() {
+  pin = "button";
+}
+
+module.exports = { Button };
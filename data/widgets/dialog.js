@@ -0,0 +1,5 @@
+function Dialog() {
+  pin = "dialog";
+}
+
+module.exports = { Dialog };
@@ -0,0 +1,8 @@
+const { Button } = require("./button");
+
+export * from "./slider";
+
+module.exports = {
+  Button,
+  ...require("./dialog"),
+};
@@ -0,0 +1,5 @@
+function Slider() {
+  pin = "slider";
+}
+
+export { Slider };
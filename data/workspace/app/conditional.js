@@ -0,0 +1,9 @@
+const conditional = require("@scope/conditional");
+const { format } = require("@scope/conditional/util");
+const installed = require("installed");
+
+function foo() {
+  conditional.greet();
+  format();
+  installed.run();
+}
@@ -0,0 +1,9 @@
+const pkg = require("@scope/pkg");
+const { helper } = require("@scope/pkg/lib/helper");
+const fs = require("fs");
+
+function foo() {
+  pin = "main";
+  pkg.greet();
+  helper();
+}
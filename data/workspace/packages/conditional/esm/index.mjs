@@ -0,0 +1,3 @@
+export function greet() {
+  pin = "esm";
+}
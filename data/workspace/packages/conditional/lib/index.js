@@ -0,0 +1,5 @@
+function greet() {
+  pin = "conditional";
+}
+
+module.exports = { greet };
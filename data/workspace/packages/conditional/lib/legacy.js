@@ -0,0 +1,5 @@
+function greet() {
+  pin = "legacy";
+}
+
+module.exports = { greet };
@@ -0,0 +1,5 @@
+function format() {
+  pin = "util";
+}
+
+module.exports = { format };
@@ -0,0 +1,5 @@
+function helper() {
+  pin = "helper";
+}
+
+module.exports = { helper };
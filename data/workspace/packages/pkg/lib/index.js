@@ -0,0 +1,8 @@
+const { helper } = require("./helper");
+
+function greet() {
+  pin = "greet";
+  helper();
+}
+
+module.exports = { greet };
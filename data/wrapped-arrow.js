@@ -0,0 +1,7 @@
+const handlers = require("./mixed/handlers");
+
+function foo() {
+  handlers.handler();
+  handlers.wrapped();
+  handlers.direct();
+}
@@ -0,0 +1,7 @@
+const store = require("./mixed/store");
+const Connected = require("./mixed/component");
+
+function foo() {
+  store();
+  Connected();
+}
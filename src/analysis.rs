@@ -0,0 +1,397 @@
+use std::{
+    collections::{BTreeSet, HashMap, HashSet},
+    io,
+};
+
+use crate::{
+    ast::{ASTNode, FunctionStatement},
+    encoding::Encodings,
+    logger::{Log, Terminal},
+    parser::Parser,
+    utils::{self, PathFilter},
+};
+use walkdir::WalkDir;
+
+/// A file read and parsed, as the reports on a single file need it.
+pub struct Source {
+    pub path: String,
+    pub src: String,
+    pub ast: ASTNode,
+}
+
+/// A source file found under a directory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilePath {
+    pub absolute: String,
+    /// the path it was found at, relative when the directory was.
+    pub path: String,
+}
+
+/// The source files under a directory, outside of node_modules, sorted by name.
+pub fn source_files(dir: &str) -> Vec<FilePath> {
+    WalkDir::new(dir)
+        .sort_by_file_name()
+        .into_iter()
+        .filter_entry(|e| e.file_name() != "node_modules")
+        .filter_map(Result::ok)
+        .map(|e| e.path().display().to_string())
+        .filter(|path| utils::is_source_file(path))
+        .filter_map(|path| {
+            Some(FilePath {
+                absolute: utils::get_absolute_path(&path).ok()?,
+                path,
+            })
+        })
+        .collect()
+}
+
+/// A name a rename would collide with.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Conflict {
+    pub path: String,
+    pub line: usize,
+    /// declared in a file importing the renamed name, rather than in the file declaring it.
+    pub importer: bool,
+}
+
+/// Everything about a file at once, see `Analyzer::summary_for_file`.
+#[derive(Debug, Clone)]
+pub struct FileSummary {
+    pub lines: usize,
+    pub functions: Vec<FunctionStatement>,
+    /// each module specifier and its line, with the file it resolves to or why it doesn't.
+    pub imports: Vec<(usize, String, Result<String, String>)>,
+    /// as described by `describe_exports`.
+    pub exports: Vec<String>,
+    /// the files importing it and the lines they do, when asked for.
+    pub importers: Option<Vec<(String, usize)>>,
+}
+
+/// The names a program exports, each described by what it refers to: a function declared in
+/// it, a class, a name it imports, data or the file it's re-exported from.
+pub fn describe_exports(ast: &ASTNode) -> Vec<String> {
+    let mut exports = vec![];
+    let describe = |name: &str| match ast.find_function(name) {
+        Some(ASTNode::FunctionStatement(fs)) => format!("{}, line {}", fs.kind, fs.start + 1),
+        // a name it requires or, as in `exports.x = lib.y`, a member of one, which searches
+        // follow into the required file.
+        _ => match ast
+            .find_binding(name.split('.').next().unwrap_or(name))
+            .and_then(|b| b.try_get_require_file())
+        {
+            Some((specifier, _)) => format!("imported from {}", specifier),
+            None if ast.functions().iter().any(|f| {
+                f.name
+                    .strip_prefix(name)
+                    .is_some_and(|m| m.starts_with('.'))
+            }) =>
+            {
+                String::from("class")
+            }
+            None => String::from("data"),
+        },
+    };
+
+    let es = ast.find_export_statement();
+    if let Some(es) = &es {
+        for prop in &es.properties {
+            let kind = prop.value.as_deref().map_or(String::from("data"), describe);
+            exports.push(format!("{} ({})", prop.key, kind));
+        }
+    }
+    match ast.find_assigned_export() {
+        // `export default greet` or `export default compose(a, b)`, by the names it refers to.
+        Some(ea) if ea.default => {
+            for name in &ea.names {
+                exports.push(match name.as_str() {
+                    "default" => format!("default ({})", describe(name)),
+                    _ => format!("default ({}, {})", name, describe(name)),
+                });
+            }
+        }
+        Some(ea) if es.is_none() => {
+            for name in &ea.names {
+                exports.push(format!("{} ({})", name, describe(name)));
+            }
+        }
+        _ => (),
+    }
+
+    for re in ast.re_exports() {
+        match &re.names {
+            Some(names) => exports.extend(
+                names
+                    .iter()
+                    .map(|p| format!("{} (re-exported from {})", p.key, re.file)),
+            ),
+            None => exports.push(format!("* (re-exported from {})", re.file)),
+        }
+    }
+    exports
+}
+
+/// The functions of a program at least `min_lines` long, nested ones included, longest first
+/// and in the order they're declared when as long.
+pub fn longest_functions(ast: &ASTNode, min_lines: usize) -> Vec<&FunctionStatement> {
+    let mut funcs = vec![];
+    let mut pending = ast.functions();
+    while let Some(func) = pending.pop() {
+        pending.extend(func.children());
+        funcs.push(func);
+    }
+    funcs.retain(|f| f.line_count() >= min_lines);
+    funcs.sort_by_key(|f| (std::cmp::Reverse(f.line_count()), f.start));
+    funcs
+}
+
+// one plus the decision points on the lines of func, its estimated cyclomatic complexity.
+fn estimate_complexity(func: &FunctionStatement, points: &[(usize, &str)]) -> usize {
+    1 + points
+        .iter()
+        .filter(|(line, _)| (func.start..=func.end).contains(line))
+        .count()
+}
+
+/// Reads the files the reports look at, each with the encoding `encodings` gives it.
+pub struct Analyzer {
+    encodings: Encodings,
+    logger: Box<dyn Log>,
+}
+
+impl Analyzer {
+    pub fn new(encodings: Encodings) -> Self {
+        Self {
+            encodings,
+            logger: Box::new(Terminal),
+        }
+    }
+
+    /// Routes warnings and errors to `logger` instead of the terminal.
+    pub fn with_logger(mut self, logger: Box<dyn Log>) -> Self {
+        self.logger = logger;
+        self
+    }
+
+    pub fn encodings(&self) -> &Encodings {
+        &self.encodings
+    }
+
+    /// Reads and parses the file at `path`, reporting a failure to read it to the logger.
+    pub fn load(&self, path: &str) -> io::Result<Source> {
+        let encoding = self.encodings.for_path(path);
+        let src = utils::read_file(path, encoding, self.logger.as_ref())?;
+        let ast = Parser::new(&src).parse();
+
+        Ok(Source {
+            path: String::from(path),
+            src,
+            ast,
+        })
+    }
+
+    /// The packages required by the file at `path` and the files it requires, each with the
+    /// files and lines requiring it. Node's builtin modules aren't included.
+    pub fn dependencies(&self, path: &str) -> io::Result<HashMap<String, Vec<(String, usize)>>> {
+        let mut deps: HashMap<String, Vec<(String, usize)>> = HashMap::new();
+        let mut pending = vec![utils::get_absolute_path(path)?];
+        let mut visited = HashSet::new();
+
+        while let Some(path) = pending.pop() {
+            if !visited.insert(path.clone()) {
+                continue;
+            }
+
+            let Ok(source) = self.load(&path) else {
+                continue;
+            };
+            for (line, specifier) in Parser::new(&source.src).import_sites() {
+                if utils::is_relative_path(&specifier) {
+                    pending
+                        .extend(utils::join_path(&path, &specifier).filter(|p| !utils::is_json(p)));
+                } else if !utils::is_node_builtin(&specifier) {
+                    let (name, _) = utils::split_package_specifier(&specifier);
+                    deps.entry(name.to_string())
+                        .or_default()
+                        .push((path.clone(), line));
+                }
+            }
+        }
+
+        for sites in deps.values_mut() {
+            sites.sort();
+            sites.dedup();
+        }
+        Ok(deps)
+    }
+
+    /// The source files under `dir` that none of the others import, other than those `filter`
+    /// excludes, such as entry points and tests.
+    pub fn orphan_files(&self, dir: &str, filter: &PathFilter) -> Vec<FilePath> {
+        let files = source_files(dir);
+
+        let mut imported = HashSet::new();
+        for file in &files {
+            let Ok(source) = self.load(&file.absolute) else {
+                continue;
+            };
+            for (_, specifier) in Parser::new(&source.src).import_sites() {
+                match utils::join_path(&file.absolute, &specifier) {
+                    Some(target) if target != file.absolute => imported.insert(target),
+                    _ => false,
+                };
+            }
+        }
+
+        files
+            .into_iter()
+            .filter(|f| !imported.contains(&f.absolute) && filter.allows(&f.path))
+            .collect()
+    }
+
+    /// Where renaming `old_name`, a top level name of the file at `path`, to `new_name` would
+    /// collide with a name already declared, in the file itself and in the files under
+    /// `importers` destructuring it by name.
+    pub fn rename_conflicts(
+        &self,
+        path: &str,
+        old_name: &str,
+        new_name: &str,
+        importers: Option<&str>,
+    ) -> io::Result<Vec<Conflict>> {
+        // loaded first so that a missing file is reported.
+        let source = self.load(path)?;
+        let abs_path = utils::get_absolute_path(path)?;
+
+        let mut conflicts = vec![];
+        for (name, line) in source.ast.declared_names() {
+            if name == new_name {
+                conflicts.push(Conflict {
+                    path: String::from(path),
+                    line,
+                    importer: false,
+                });
+            }
+        }
+
+        for file in importers.map(source_files).unwrap_or_default() {
+            if file.absolute == abs_path {
+                continue;
+            }
+
+            let Ok(Source { ast, .. }) = self.load(&file.absolute) else {
+                continue;
+            };
+            let ASTNode::Program(prog) = &ast else {
+                continue;
+            };
+
+            let imports_old = prog
+                .lines
+                .iter()
+                .any(|node| match node.try_get_require_file() {
+                    Some((specifier, ve)) => {
+                        utils::join_path(&file.absolute, &specifier).as_ref() == Some(&abs_path)
+                            && matches!(ve.lhs.as_ref(), ASTNode::ObjectPattern(op)
+                            if op.get_value(old_name).is_some_and(|v| v == old_name))
+                    }
+                    None => false,
+                });
+            if !imports_old {
+                continue;
+            }
+
+            for (name, line) in ast.declared_names() {
+                if name == new_name {
+                    conflicts.push(Conflict {
+                        path: file.path.clone(),
+                        line,
+                        importer: true,
+                    });
+                }
+            }
+        }
+
+        Ok(conflicts)
+    }
+
+    /// The size, functions, imports and exports of the file at `path`, and the files under
+    /// `importers` that import it. None when the file can't be read.
+    pub fn summary_for_file(&self, path: &str, importers: Option<&str>) -> Option<FileSummary> {
+        let Source { src, ast, .. } = self.load(path).ok()?;
+        let abs_path = utils::get_absolute_path(path).ok()?;
+
+        let imports = Parser::new(&src)
+            .import_sites()
+            .into_iter()
+            .map(|(line, specifier)| {
+                let resolved = match utils::is_node_builtin(&specifier) {
+                    true => Err(String::from("node builtin")),
+                    false => utils::resolve_require(path, &specifier),
+                };
+                (line, specifier, resolved)
+            })
+            .collect();
+
+        let importers = importers.map(|dir| {
+            let mut sites = vec![];
+            for file in source_files(dir) {
+                let Ok(source) = self.load(&file.absolute) else {
+                    continue;
+                };
+                for (line, specifier) in Parser::new(&source.src).import_sites() {
+                    if utils::join_path(&file.absolute, &specifier).as_ref() == Some(&abs_path) {
+                        sites.push((file.path.clone(), line));
+                    }
+                }
+            }
+            sites
+        });
+
+        Some(FileSummary {
+            lines: src.lines().count(),
+            functions: ast.functions().into_iter().cloned().collect(),
+            imports,
+            exports: describe_exports(&ast),
+            importers,
+        })
+    }
+
+    /// Every top level function name declared in the source files under `dir`, sorted and
+    /// without repeats.
+    pub fn all_function_names(&self, dir: &str) -> BTreeSet<String> {
+        let mut names = BTreeSet::new();
+        for file in source_files(dir) {
+            let Ok(source) = self.load(&file.absolute) else {
+                continue;
+            };
+            names.extend(source.ast.functions().into_iter().map(|f| f.name.clone()));
+        }
+        names
+    }
+
+    /// The estimated cyclomatic complexity of the function `name` in the file at `path`: one
+    /// plus the decision points on its lines. None when the file can't be read or doesn't
+    /// declare it.
+    pub fn complexity(&self, path: &str, name: &str) -> Option<usize> {
+        let Source { src, ast, .. } = self.load(path).ok()?;
+        let ASTNode::FunctionStatement(func) = ast.find_function(name)? else {
+            return None;
+        };
+        Some(estimate_complexity(
+            func,
+            &Parser::new(&src).decision_points(),
+        ))
+    }
+
+    /// The top level functions of the file at `path`, each with its estimated cyclomatic
+    /// complexity as `complexity` gives it.
+    pub fn complexities(&self, path: &str) -> io::Result<Vec<(FunctionStatement, usize)>> {
+        let Source { src, ast, .. } = self.load(path)?;
+        let points = Parser::new(&src).decision_points();
+        Ok(ast
+            .functions()
+            .into_iter()
+            .map(|func| (func.clone(), estimate_complexity(func, &points)))
+            .collect())
+    }
+}
@@ -7,6 +7,8 @@ pub enum ASTNode {
     VariableExpression(VariableExpression),
     ObjectPattern(ObjectPattern),
     ExportStatement(ObjectPattern),
+    ReExportStatement(ReExportStatement),
+    ExportAssignment(ExportAssignment),
     Identifier(Identifier),
     MemberExpression(MemberExpression),
 }
@@ -20,12 +22,73 @@ pub struct Program {
     pub end: Line,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum FunctionKind {
+    Declaration,
+    Arrow,
+    Method,
+    /// a function invoked as soon as it's declared, as in `const x = (() => {})()`.
+    Iife,
+}
+
+impl std::fmt::Display for FunctionKind {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let s = match self {
+            FunctionKind::Declaration => "declaration",
+            FunctionKind::Arrow => "arrow",
+            FunctionKind::Method => "method",
+            FunctionKind::Iife => "iife",
+        };
+
+        write!(f, "{}", s)
+    }
+}
+
+impl std::str::FromStr for FunctionKind {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "declaration" => Ok(FunctionKind::Declaration),
+            "arrow" => Ok(FunctionKind::Arrow),
+            "method" => Ok(FunctionKind::Method),
+            "iife" => Ok(FunctionKind::Iife),
+            _ => Err(format!("unknown function kind '{}'", s)),
+        }
+    }
+}
+
 #[derive(Debug, Clone)]
 pub struct FunctionStatement {
     pub name: String,
+    pub kind: FunctionKind,
     pub body: Box<ASTNode>,
     pub start: Line,
     pub end: Line,
+    /// the byte range of the function's source, from its declaration to the closing brace.
+    pub start_byte: usize,
+    pub end_byte: usize,
+}
+
+impl FunctionStatement {
+    pub fn line_count(&self) -> usize {
+        self.end - self.start + 1
+    }
+
+    /// Functions declared directly inside this one, including within its nested blocks.
+    pub fn children(&self) -> Vec<&FunctionStatement> {
+        fn collect<'a>(node: &'a ASTNode, out: &mut Vec<&'a FunctionStatement>) {
+            match node {
+                ASTNode::FunctionStatement(fs) => out.push(fs),
+                ASTNode::BlockStatement(bs) => bs.body.iter().for_each(|n| collect(n, out)),
+                _ => (),
+            }
+        }
+
+        let mut children = vec![];
+        collect(&self.body, &mut children);
+        children
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -39,7 +102,8 @@ pub struct VariableExpression {
 #[derive(Debug, Clone)]
 pub struct Property {
     pub key: String,
-    pub value: String,
+    /// the local name bound to the key, None when the value isn't a plain identifier.
+    pub value: Option<String>,
 }
 
 #[derive(Debug, Clone)]
@@ -53,13 +117,33 @@ impl ObjectPattern {
     pub fn get_value(&self, key: &str) -> Option<&String> {
         for prop in &self.properties {
             if prop.key == key {
-                return Some(&prop.value);
+                return prop.value.as_ref();
             }
         }
         None
     }
 }
 
+#[derive(Debug, Clone)]
+pub struct ReExportStatement {
+    pub file: String,
+    /// the exported names and the names they refer to in `file`, None to re-export all of it
+    /// as `module.exports = require(file)` and `export * from file` do.
+    pub names: Option<Vec<Property>>,
+    pub start: Line,
+    pub end: Line,
+}
+
+/// `module.exports = foo`, or the functions wrapped by `module.exports = compose(a, b)`.
+#[derive(Debug, Clone)]
+pub struct ExportAssignment {
+    pub names: Vec<String>,
+    /// assigned by an es module's `export default`, rather than to module.exports.
+    pub default: bool,
+    pub start: Line,
+    pub end: Line,
+}
+
 #[derive(Debug, Clone)]
 pub struct BlockStatement {
     pub body: Box<Vec<ASTNode>>,
@@ -71,6 +155,8 @@ pub struct BlockStatement {
 pub struct CallExpression {
     pub base: Box<ASTNode>,
     pub param: Option<String>,
+    /// identifiers passed directly as arguments.
+    pub args: Vec<String>,
     pub start: Line,
     pub end: Line,
 }
@@ -91,13 +177,28 @@ pub struct MemberExpression {
 }
 
 impl MemberExpression {
-    pub fn get_base(&self) -> &Identifier {
+    /// The identifier the expression starts from, None when it starts from a call or other
+    /// expression as in `foo().bar`.
+    pub fn get_base(&self) -> Option<&Identifier> {
         match self.base.as_ref() {
-            ASTNode::MemberExpression(me) => return me.get_base(),
-            ASTNode::Identifier(ident) => return ident,
-            _ => panic!("invalid membership expression"),
+            ASTNode::MemberExpression(me) => me.get_base(),
+            ASTNode::Identifier(ident) => Some(ident),
+            _ => None,
         }
     }
+
+    /// The properties between the base identifier and the last property, `b` and `c` of
+    /// `a.b.c.d`, empty for `a.b`.
+    pub fn get_namespaces(&self) -> Vec<&String> {
+        let mut namespaces = vec![];
+        let mut base = self.base.as_ref();
+        while let ASTNode::MemberExpression(me) = base {
+            namespaces.push(&me.property);
+            base = me.base.as_ref();
+        }
+        namespaces.reverse();
+        namespaces
+    }
 }
 
 impl ASTNode {
@@ -106,10 +207,12 @@ impl ASTNode {
             ASTNode::BlockStatement(bs) => bs.start,
             ASTNode::CallExpression(ce) => ce.start,
             ASTNode::Identifier(ident) => ident.start,
+            ASTNode::ExportAssignment(ea) => ea.start,
             ASTNode::FunctionStatement(fs) => fs.start,
             ASTNode::Program(p) => p.start,
             ASTNode::VariableExpression(ve) => ve.start,
             ASTNode::MemberExpression(me) => me.start,
+            ASTNode::ReExportStatement(re) => re.start,
             ASTNode::ObjectPattern(op) | ASTNode::ExportStatement(op) => op.start,
         }
     }
@@ -119,14 +222,80 @@ impl ASTNode {
             ASTNode::BlockStatement(bs) => bs.end,
             ASTNode::CallExpression(ce) => ce.end,
             ASTNode::Identifier(ident) => ident.end,
+            ASTNode::ExportAssignment(ea) => ea.end,
             ASTNode::FunctionStatement(fs) => fs.end,
             ASTNode::Program(p) => p.end,
             ASTNode::VariableExpression(ve) => ve.end,
             ASTNode::MemberExpression(me) => me.end,
+            ASTNode::ReExportStatement(re) => re.end,
             ASTNode::ObjectPattern(op) | ASTNode::ExportStatement(op) => op.end,
         }
     }
 
+    pub fn functions(&self) -> Vec<&FunctionStatement> {
+        let prog_lines = match self {
+            ASTNode::Program(prog) => &prog.lines,
+            _ => return vec![],
+        };
+
+        prog_lines
+            .iter()
+            .filter_map(|node| match node {
+                ASTNode::FunctionStatement(fs) => Some(fs),
+                _ => None,
+            })
+            .collect()
+    }
+
+    /// The names declared at the top level of the program with their lines, those of
+    /// functions and those bound by `const x = ...` or `const { a, b } = ...`.
+    pub fn declared_names(&self) -> Vec<(&str, Line)> {
+        let prog_lines = match self {
+            ASTNode::Program(prog) => &prog.lines,
+            _ => return vec![],
+        };
+
+        let mut names = vec![];
+        for node in prog_lines.iter() {
+            match node {
+                ASTNode::FunctionStatement(fs) if fs.kind != FunctionKind::Method => {
+                    names.push((fs.name.as_str(), fs.start))
+                }
+                ASTNode::VariableExpression(ve) => match ve.lhs.as_ref() {
+                    ASTNode::Identifier(ident) => names.push((ident.name.as_str(), ve.start)),
+                    ASTNode::ObjectPattern(op) => names.extend(
+                        op.properties
+                            .iter()
+                            .filter_map(|p| Some((p.value.as_deref()?, ve.start))),
+                    ),
+                    _ => (),
+                },
+                _ => (),
+            }
+        }
+        names
+    }
+
+    /// The top level `const`, `let` or `var` statement that binds `name`.
+    pub fn find_binding(&self, name: &str) -> Option<&ASTNode> {
+        let prog_lines = match self {
+            ASTNode::Program(prog) => &prog.lines,
+            _ => return None,
+        };
+
+        prog_lines.iter().find(|node| match node {
+            ASTNode::VariableExpression(ve) => match ve.lhs.as_ref() {
+                ASTNode::Identifier(ident) => ident.name == name,
+                ASTNode::ObjectPattern(op) => op
+                    .properties
+                    .iter()
+                    .any(|p| p.value.as_deref() == Some(name)),
+                _ => false,
+            },
+            _ => false,
+        })
+    }
+
     pub fn find_function(&self, name: &str) -> Option<&ASTNode> {
         let prog_lines = match self {
             ASTNode::Program(prog) => &prog.lines,
@@ -159,17 +328,9 @@ impl ASTNode {
             _ => return None,
         }
 
-        let require_file = if let Some(param) = &ce.param {
-            param
-        } else {
-            return None;
-        };
+        let require_file = ce.param.as_ref()?;
 
-        if !require_file.starts_with("./") && !require_file.starts_with("../") {
-            return None;
-        }
-
-        return Some((require_file.clone(), ve));
+        Some((require_file.clone(), ve))
     }
 
     pub fn try_export_extract(&self) -> Option<(String, &ASTNode)> {
@@ -179,39 +340,76 @@ impl ASTNode {
         }
     }
 
-    fn find_node(&self, pred: impl Fn(&ASTNode) -> bool) -> Option<&ASTNode> {
+    /// Merges every export statement in the program, a module may export piece by piece.
+    pub fn find_export_statement(&self) -> Option<ObjectPattern> {
         let prog_lines = match self {
             ASTNode::Program(prog) => &prog.lines,
             _ => return None,
         };
 
-        for node in prog_lines.as_ref() {
-            if pred(node) {
-                return Some(node);
+        let mut exports: Option<ObjectPattern> = None;
+        for node in prog_lines.iter() {
+            if let ASTNode::ExportStatement(es) = node {
+                match &mut exports {
+                    Some(e) => e.properties.extend(es.properties.iter().cloned()),
+                    None => exports = Some(es.clone()),
+                }
             }
         }
 
-        return None;
+        exports
     }
 
-    pub fn find_export_statement(&self) -> Option<&ObjectPattern> {
-        if let Some(ASTNode::ExportStatement(es)) = self.find_node(|node| match node {
-            ASTNode::ExportStatement(_) => true,
-            _ => false,
-        }) {
-            return Some(es);
-        }
-
-        None
+    pub fn find_exported_func(&self, target: &str) -> Option<&ASTNode> {
+        let es = self.find_export_statement()?;
+        let val = es.get_value(target)?;
+        self.find_function(val)
     }
 
-    pub fn find_exported_func(&self, target: &str) -> Option<&ASTNode> {
-        if let Some(es) = self.find_export_statement() {
-            if let Some(val) = es.get_value(target) {
-                return self.find_function(val);
+    /// What a module exports as a whole, `module.exports = foo` or `export = foo`.
+    /// Every `module.exports = x` in the program, merged, including those in the branches of
+    /// an `if (__DEV__) ... else ...` so that each candidate is followed.
+    pub fn find_assigned_export(&self) -> Option<ExportAssignment> {
+        fn collect(nodes: &[ASTNode], exports: &mut Option<ExportAssignment>) {
+            for node in nodes {
+                match node {
+                    ASTNode::ExportAssignment(ea) => match exports {
+                        Some(e) => e.names.extend(ea.names.iter().cloned()),
+                        None => *exports = Some(ea.clone()),
+                    },
+                    ASTNode::BlockStatement(bs) => collect(&bs.body, exports),
+                    _ => (),
+                }
             }
         }
 
-        None
+        let prog_lines = match self {
+            ASTNode::Program(prog) => &prog.lines,
+            _ => return None,
+        };
+
+        let mut exports = None;
+        collect(prog_lines, &mut exports);
+        exports
+    }
+
+    /// The first re-export of a whole module.
+    pub fn find_re_export(&self) -> Option<&ReExportStatement> {
+        self.re_exports().into_iter().find(|re| re.names.is_none())
+    }
+
+    pub fn re_exports(&self) -> Vec<&ReExportStatement> {
+        let prog_lines = match self {
+            ASTNode::Program(prog) => &prog.lines,
+            _ => return vec![],
+        };
+
+        prog_lines
+            .iter()
+            .filter_map(|node| match node {
+                ASTNode::ReExportStatement(re) => Some(re),
+                _ => None,
+            })
+            .collect()
     }
 }
@@ -0,0 +1,75 @@
+use crate::{logger::Log, utils};
+use std::str::FromStr;
+
+/// One of the encodings the WHATWG Encoding Standard defines, utf-8 by default.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Encoding(&'static encoding_rs::Encoding);
+
+impl Default for Encoding {
+    fn default() -> Self {
+        Encoding(encoding_rs::UTF_8)
+    }
+}
+
+impl FromStr for Encoding {
+    type Err = String;
+
+    /// Any of the standard's labels, such as `utf-8`, `latin1` or `shift_jis`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match encoding_rs::Encoding::for_label(s.trim().as_bytes()) {
+            Some(encoding) => Ok(Encoding(encoding)),
+            None => Err(format!("unsupported encoding '{}'", s)),
+        }
+    }
+}
+
+impl Encoding {
+    pub fn decode(&self, filename: &str, bytes: Vec<u8>, logger: &dyn Log) -> String {
+        let (text, _, had_errors) = self.0.decode(&bytes);
+        if had_errors {
+            logger.warn(
+                format!(
+                    "{} is not valid {}, decoding lossily",
+                    filename,
+                    self.0.name().to_lowercase()
+                )
+                .as_str(),
+            );
+        }
+        text.into_owned()
+    }
+
+    /// Decodes without reporting invalid input, for text that's only printed.
+    pub fn decode_lossy(&self, bytes: Vec<u8>) -> String {
+        self.0.decode(&bytes).0.into_owned()
+    }
+}
+
+/// The encoding to read each file with, the first glob matching a file wins.
+#[derive(Debug, Clone, Default)]
+pub struct Encodings {
+    pub default: Encoding,
+    pub by_glob: Vec<(String, Encoding)>,
+}
+
+impl Encodings {
+    /// Parses `ENCODING` as the default or `GLOB=ENCODING` as an override.
+    pub fn add(&mut self, arg: &str) -> Result<(), String> {
+        match arg.rsplit_once('=') {
+            Some((glob, encoding)) => self.by_glob.push((glob.to_string(), encoding.parse()?)),
+            None => self.default = arg.parse()?,
+        };
+
+        Ok(())
+    }
+
+    pub fn for_path(&self, path: &str) -> Encoding {
+        for (glob, encoding) in &self.by_glob {
+            if utils::path_matches(glob, path) {
+                return *encoding;
+            }
+        }
+
+        self.default
+    }
+}
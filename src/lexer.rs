@@ -48,10 +48,12 @@ pub fn bytes_to_string(bytes: Vec<u8>) -> String {
 }
 
 fn is_whitespace(byte: u8) -> bool {
-    match byte {
-        b' ' | b'\t' | b'\r' => true,
-        _ => false,
-    }
+    matches!(byte, b' ' | b'\t' | b'\r')
+}
+
+// bytes of multibyte characters are treated as part of an identifier, as in `après`.
+fn is_identifier_byte(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || byte == b'_' || byte == b'$' || !byte.is_ascii()
 }
 
 #[derive(Clone)]
@@ -59,6 +61,9 @@ pub struct Cursor {
     pos: usize,
     pub line_num: usize,
     prev: TokenType,
+    // byte offsets of where the current token starts and the one before it ended.
+    token_start: usize,
+    prev_end: usize,
 }
 
 impl Default for Cursor {
@@ -67,6 +72,8 @@ impl Default for Cursor {
             pos: 0,
             line_num: 0,
             prev: TokenType::Whitespace,
+            token_start: 0,
+            prev_end: 0,
         }
     }
 }
@@ -92,7 +99,7 @@ impl Lexer {
         let mut bytes = vec![];
         for byte in self.src.iter().skip(self.cursor.pos + offset) {
             if pred(byte) {
-                bytes.push(byte.clone())
+                bytes.push(*byte)
             } else {
                 break;
             }
@@ -102,7 +109,7 @@ impl Lexer {
     }
 
     fn read_identifier(&self) -> (TokenType, usize) {
-        let (bytes, bytes_read) = self.read_while(|b| b.is_ascii_alphanumeric() || *b == b'_', 0);
+        let (bytes, bytes_read) = self.read_while(|b| is_identifier_byte(*b), 0);
 
         (TokenType::Identifier(bytes_to_string(bytes)), bytes_read)
     }
@@ -149,18 +156,20 @@ impl Lexer {
             b'\n' => (TokenType::Newline, 1),
             b if *b == b'"' || *b == b'\'' => self.read_string(*b),
             b if is_whitespace(*b) => self.read_whitespace(),
-            b if b.is_ascii_alphabetic() => self.read_identifier(),
+            b if is_identifier_byte(*b) && !b.is_ascii_digit() => self.read_identifier(),
             _ => self.read_catch_all(*byte),
         }
     }
 
     pub fn next_token(&mut self) -> TokenType {
+        self.cursor.prev_end = self.cursor.pos;
         loop {
             if self.cursor.prev == TokenType::Newline {
                 self.cursor.line_num += 1;
             }
 
             let (token, bytes_read) = self.peak();
+            self.cursor.token_start = self.cursor.pos;
             self.cursor.pos += bytes_read;
             self.cursor.prev = token.clone();
 
@@ -170,6 +179,16 @@ impl Lexer {
         }
     }
 
+    /// The byte offset the current token starts at.
+    pub fn token_start(&self) -> usize {
+        self.cursor.token_start
+    }
+
+    /// The byte offset just past the token before the current one.
+    pub fn prev_token_end(&self) -> usize {
+        self.cursor.prev_end
+    }
+
     pub fn lookahead(&mut self, distance: usize) -> TokenType {
         let mut i = distance as u32;
         let cursor_snapshot = self.cursor.clone();
@@ -181,7 +200,7 @@ impl Lexer {
             }
 
             i -= 1;
-            if i <= 0 || token == TokenType::EOF {
+            if i == 0 || token == TokenType::EOF {
                 self.cursor = cursor_snapshot;
                 return token;
             }
@@ -1,4 +1,6 @@
+pub mod analysis;
 pub mod ast;
+pub mod encoding;
 pub mod file_scope;
 pub mod lexer;
 pub mod logger;
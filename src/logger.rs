@@ -1,4 +1,4 @@
-const ESCAPE: &'static str = "\x1b[0m";
+const ESCAPE: &str = "\x1b[0m";
 
 fn red(msg: &str) -> String {
     format!("\x1b[31m{}{}", msg, ESCAPE)
@@ -23,3 +23,42 @@ pub fn warn(msg: &str) {
 pub fn info(msg: &str) {
     println!("{}", green(msg));
 }
+
+/// A destination for the messages produced while searching.
+pub trait Log {
+    fn err(&self, msg: &str);
+    fn warn(&self, msg: &str);
+    fn info(&self, msg: &str);
+}
+
+/// Prints coloured messages to the terminal, the default.
+pub struct Terminal;
+
+impl Log for Terminal {
+    fn err(&self, msg: &str) {
+        err(msg)
+    }
+
+    fn warn(&self, msg: &str) {
+        warn(msg)
+    }
+
+    fn info(&self, msg: &str) {
+        info(msg)
+    }
+}
+
+/// Drops matches, printing only warnings and errors, both to stderr.
+pub struct Quiet;
+
+impl Log for Quiet {
+    fn err(&self, msg: &str) {
+        err(msg)
+    }
+
+    fn warn(&self, msg: &str) {
+        eprintln!("{}", yellow(msg));
+    }
+
+    fn info(&self, _msg: &str) {}
+}
@@ -1,22 +1,570 @@
-use cjsgrep::visitor::ASTVisitor;
-use clap::{command, Arg};
-use std::env;
+use cjsgrep::{
+    analysis::{self, Analyzer, Source},
+    ast::{ASTNode, FunctionKind, FunctionStatement},
+    encoding::Encodings,
+    logger::{self, Quiet},
+    parser::{Parser, ROUTE_METHODS},
+    utils::{self, PathFilter},
+    visitor::{ASTVisitor, Options},
+};
+use clap::{command, Arg, ArgAction, Command};
+use std::{path::Path, process, thread, time::Duration};
 
-fn parse_file(filename: &str, pattern: &str, func_start: Option<&str>) {
-    // let src = match fs::read_to_string(filename) {
-    //     Ok(s) => s,
-    //     Err(err) => {
-    //         eprintln!("failed to read file: {}", err.to_string());
-    //         process::exit(1);
-    //     }
-    // };
+fn parse_file(
+    filename: &str,
+    pattern: &str,
+    func_start: Option<&str>,
+    options: &Options,
+) -> Result<ASTVisitor, String> {
+    let mut visitor = ASTVisitor::with_options(pattern, options.clone());
+    visitor.search(filename, func_start)?;
+    print_timings(&visitor);
+    Ok(visitor)
+}
+
+fn print_timings(visitor: &ASTVisitor) {
+    for timing in visitor.timings() {
+        eprintln!(
+            "{}: read {:.2?}, parsed {:.2?}",
+            timing.path, timing.read, timing.parse
+        );
+    }
+}
+
+// re-runs the search whenever one of the files it read changes, until interrupted. The
+// starting file and its directory are always watched, so that a search that failed, as on a
+// missing file, runs again once the file is there.
+fn watch(filename: &str, pattern: &str, func_start: Option<&str>, options: &Options) -> ! {
+    let dir = Path::new(filename)
+        .parent()
+        .filter(|dir| !dir.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    loop {
+        let mut files = vec![filename.to_string(), dir.display().to_string()];
+        match parse_file(filename, pattern, func_start, options) {
+            Ok(visitor) => files.extend(visitor.files_read()),
+            Err(e) => logger::err(&e),
+        }
+        let snapshot: Vec<_> = files.iter().map(|f| utils::modified_time(f)).collect();
+
+        while files
+            .iter()
+            .map(|f| utils::modified_time(f))
+            .eq(snapshot.iter().cloned())
+        {
+            thread::sleep(Duration::from_millis(500));
+        }
+
+        logger::warn("--- files changed, searching again ---");
+    }
+}
+
+// loads a file the subcommand can't go on without, exiting when it can't be read.
+fn load(analyzer: &Analyzer, filename: &str) -> Source {
+    analyzer.load(filename).unwrap_or_else(|_| process::exit(1))
+}
+
+fn resolve(filename: &str, specifier: &str) {
+    if !utils::is_file(filename) {
+        logger::err(format!("file {} not found", filename).as_str());
+        process::exit(1);
+    }
+
+    match utils::resolve_require_candidates(filename, specifier) {
+        Ok(paths) => {
+            if paths.len() > 1 {
+                eprintln!("'{}' could be any of {}", specifier, paths.join(", "));
+            }
+            println!("{}", paths[0]);
+        }
+        Err(e) => {
+            logger::err(&e);
+            process::exit(1);
+        }
+    }
+}
+
+fn print_functions(
+    funcs: Vec<&FunctionStatement>,
+    kind: Option<FunctionKind>,
+    depth: Option<usize>,
+    bytes: bool,
+) {
+    for func in funcs {
+        if kind.is_none_or(|k| k == func.kind) {
+            let indent = "  ".repeat(depth.unwrap_or(0));
+            let range = match bytes {
+                true => format!(" {}..{}", func.start_byte, func.end_byte),
+                false => String::new(),
+            };
+            println!(
+                "{}{}: {} ({}){}",
+                indent,
+                func.start + 1,
+                func.name,
+                func.kind,
+                range
+            );
+        }
+
+        if let Some(depth) = depth {
+            print_functions(func.children(), kind, Some(depth + 1), bytes);
+        }
+    }
+}
+
+fn list_functions(
+    analyzer: &Analyzer,
+    filename: &str,
+    kind: Option<FunctionKind>,
+    nested: bool,
+    bytes: bool,
+) {
+    let ast = load(analyzer, filename).ast;
+    print_functions(ast.functions(), kind, nested.then_some(0), bytes);
+}
+
+// lists the longest functions in a file, nested ones included, longest first.
+fn list_longest_functions(
+    analyzer: &Analyzer,
+    filename: &str,
+    count: Option<usize>,
+    min_lines: usize,
+) {
+    let ast = load(analyzer, filename).ast;
+    let funcs = analysis::longest_functions(&ast, min_lines);
+    for func in funcs.into_iter().take(count.unwrap_or(usize::MAX)) {
+        println!(
+            "{}: {} ({}) {} lines",
+            func.start + 1,
+            func.name,
+            func.kind,
+            func.line_count()
+        );
+    }
+}
+
+// estimates the cyclomatic complexity of a file's top level functions, or only of `name`, as
+// one more than the branches within the function's lines, nested functions' included.
+fn list_complexity(analyzer: &Analyzer, filename: &str, name: Option<&str>) {
+    let Some(name) = name else {
+        let funcs = analyzer
+            .complexities(filename)
+            .unwrap_or_else(|_| process::exit(1));
+        for (func, complexity) in funcs {
+            println!(
+                "{}: {} ({}) {}",
+                func.start + 1,
+                func.name,
+                func.kind,
+                complexity
+            );
+        }
+        return;
+    };
+
+    match analyzer.complexity(filename, name) {
+        Some(complexity) => println!("{}", complexity),
+        // a file that can't be read has already been reported.
+        None if !utils::is_file(filename) => process::exit(1),
+        None => {
+            logger::err(&format!("function {} not found", name));
+            process::exit(1);
+        }
+    }
+}
+
+// prints a function's source, streamed from the file rather than kept from parsing.
+fn show_function(analyzer: &Analyzer, filename: &str, name: &str) {
+    let range = match load(analyzer, filename).ast.find_function(name) {
+        Some(ASTNode::FunctionStatement(fs)) => Some(fs.start..=fs.end),
+        _ => None,
+    };
+
+    if range.is_none() {
+        logger::err(&format!("function {} not found", name));
+        process::exit(1);
+    }
+
+    let start = range.as_ref().map_or(0, |r| *r.start());
+    let encoding = analyzer.encodings().for_path(filename);
+    for (i, line) in utils::read_lines(filename, range, encoding).enumerate() {
+        println!("{}: {}", start + i + 1, line);
+    }
+}
+
+// prints the source of the function a name in a file refers to, following requires to the
+// file declaring it and naming that file first when it's another.
+fn print_definition(filename: &str, name: &str, preserve_indentation: bool, encodings: &Encodings) {
+    let mut visitor = ASTVisitor::with_options(
+        "",
+        Options {
+            preserve_indentation,
+            encodings: encodings.clone(),
+            ..Options::default()
+        },
+    );
+    let symbols = visitor.definition_of(filename, name);
+    if symbols.is_empty() {
+        logger::err(&format!("function {} not found in {}", name, filename));
+        process::exit(1);
+    }
+
+    for symbol in symbols {
+        let ASTNode::FunctionStatement(fs) = &symbol.node else {
+            logger::err(&format!("{} is not a function", name));
+            process::exit(1);
+        };
+
+        if utils::get_absolute_path(filename).is_ok_and(|path| path != symbol.file_path) {
+            println!("{}", symbol.file_path);
+        }
+        let encoding = encodings.for_path(&symbol.file_path);
+        let lines = utils::read_lines(&symbol.file_path, Some(fs.start..=fs.end), encoding);
+        for (i, line) in lines.enumerate() {
+            let content = match preserve_indentation {
+                true => line.trim_end(),
+                false => line.trim(),
+            };
+            println!("{}: {}", fs.start + i + 1, content);
+        }
+    }
+}
+
+// the packages required by a file and the files it requires, with where each is required.
+fn list_dependencies(analyzer: &Analyzer, filename: &str) {
+    let deps = analyzer.dependencies(filename).unwrap_or_else(|e| {
+        logger::err(&format!("file {} not found: {}", filename, e));
+        process::exit(1);
+    });
+
+    let mut names: Vec<_> = deps.keys().collect();
+    names.sort();
+    for name in names {
+        println!("{}", name);
+        for (path, line) in &deps[name] {
+            println!("  {}:{}", path, line + 1);
+        }
+    }
+}
+
+// every top level function name declared under a directory, sorted and without repeats, as
+// candidates for completing a starting function name.
+fn list_function_names(analyzer: &Analyzer, dir: &str) {
+    for name in analyzer.all_function_names(dir) {
+        println!("{}", name);
+    }
+}
+
+// lists the source files under a directory that no other file there imports.
+fn list_orphans(analyzer: &Analyzer, dir: &str, filter: &PathFilter) {
+    for file in analyzer.orphan_files(dir, filter) {
+        println!("{}", file.path);
+    }
+}
+
+// reports where renaming a top level name of a file would collide with a name already
+// declared, in the file itself and in the files under `importers` destructuring it by name.
+fn rename_check(
+    analyzer: &Analyzer,
+    filename: &str,
+    old_name: &str,
+    new_name: &str,
+    importers: Option<&str>,
+) {
+    let conflicts = analyzer
+        .rename_conflicts(filename, old_name, new_name, importers)
+        .unwrap_or_else(|_| process::exit(1));
+
+    for conflict in &conflicts {
+        match conflict.importer {
+            false => println!(
+                "{}:{}: '{}' is already declared",
+                conflict.path,
+                conflict.line + 1,
+                new_name
+            ),
+            true => println!(
+                "{}:{}: '{}' is already declared where '{}' is imported from {}",
+                conflict.path,
+                conflict.line + 1,
+                new_name,
+                old_name,
+                filename
+            ),
+        }
+    }
+    if !conflicts.is_empty() {
+        process::exit(1);
+    }
+}
+
+fn list_dynamic_code(analyzer: &Analyzer, filename: &str) {
+    let src = load(analyzer, filename).src;
+    for (line, site) in Parser::new(&src).dynamic_code_sites() {
+        println!("{}: {}", line + 1, site);
+    }
+}
+
+// lists the routes registered in each file, with the handler of each.
+fn list_routes(
+    analyzer: &Analyzer,
+    filenames: Vec<&String>,
+    methods: Vec<String>,
+    handler_arg: Option<usize>,
+) {
+    for filename in filenames {
+        let src = load(analyzer, filename).src;
+        for (line, site) in Parser::new(&src).route_sites(&methods, handler_arg) {
+            println!(
+                "{}:{}: {} {} {}",
+                filename,
+                line + 1,
+                site.method.to_uppercase(),
+                site.route,
+                site.handler
+            );
+        }
+    }
+}
 
-    let mut visitor = ASTVisitor::new(pattern);
-    let _ = visitor.search(filename, func_start);
+// lists the names a file exports, and whether each refers to a function declared in it.
+fn list_exports(analyzer: &Analyzer, filename: &str) {
+    for export in analysis::describe_exports(&load(analyzer, filename).ast) {
+        println!("{}", export);
+    }
+}
+
+// everything about a file at once, its size, functions, imports and exports, and the files
+// under `importers` that import it.
+fn summarize(analyzer: &Analyzer, filename: &str, importers: Option<&str>) {
+    let Some(summary) = analyzer.summary_for_file(filename, importers) else {
+        process::exit(1);
+    };
+    println!(
+        "{}: {} lines, {} functions, {} imports, {} exports",
+        filename,
+        summary.lines,
+        summary.functions.len(),
+        summary.imports.len(),
+        summary.exports.len()
+    );
+
+    println!("functions:");
+    for func in &summary.functions {
+        println!(
+            "  {}: {} ({}) {} lines",
+            func.start + 1,
+            func.name,
+            func.kind,
+            func.line_count()
+        );
+    }
+
+    println!("imports:");
+    for (line, specifier, resolved) in &summary.imports {
+        let resolved = match resolved {
+            Ok(path) => path,
+            Err(reason) => reason,
+        };
+        println!("  {}: {} -> {}", line + 1, specifier, resolved);
+    }
+
+    println!("exports:");
+    for export in &summary.exports {
+        println!("  {}", export);
+    }
+
+    let Some(importers) = &summary.importers else {
+        return;
+    };
+    println!("importers:");
+    for (path, line) in importers {
+        println!("  {}:{}", path, line + 1);
+    }
 }
 
 fn main() {
     let matches = command!()
+        .args_conflicts_with_subcommands(true)
+        .subcommand_negates_reqs(true)
+        .disable_help_subcommand(true)
+        .subcommand(
+            Command::new("analyze")
+                .about("report on a file or directory instead of searching, see the subcommands")
+                .subcommand_required(true)
+                .subcommand(
+                    Command::new("resolve")
+                        .about("print the file a require specifier resolves to")
+                        .arg(
+                            Arg::new("filepath")
+                                .required(true)
+                                .help("the file containing the require"),
+                        )
+                        .arg(
+                            Arg::new("specifier")
+                                .required(true)
+                                .help("the specifier passed to require"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("functions")
+                        .about("list the top level functions declared in a file")
+                        .arg(Arg::new("filepath").required(true).help("the file to list"))
+                        .arg(
+                            Arg::new("kind")
+                                .long("kind")
+                                .value_parser(clap::value_parser!(FunctionKind))
+                                .help("only list functions of this kind, declaration, arrow, method or iife"),
+                        )
+                        .arg(
+                            Arg::new("nested")
+                                .long("nested")
+                                .action(ArgAction::SetTrue)
+                                .help("also list the functions declared inside each function, indented"),
+                        )
+                        .arg(
+                            Arg::new("bytes")
+                                .long("bytes")
+                                .action(ArgAction::SetTrue)
+                                .help("also print the byte range of each function's source"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("longest")
+                        .about("list the longest functions in a file, including nested ones")
+                        .arg(Arg::new("filepath").required(true).help("the file to list"))
+                        .arg(
+                            Arg::new("count")
+                                .short('n')
+                                .long("count")
+                                .value_parser(clap::value_parser!(usize))
+                                .help("only list this many functions"),
+                        )
+                        .arg(
+                            Arg::new("min-lines")
+                                .long("min-lines")
+                                .value_name("LINES")
+                                .value_parser(clap::value_parser!(usize))
+                                .help("only list functions at least this many lines long"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("complexity")
+                        .about("estimate the cyclomatic complexity of the top level functions in a file")
+                        .arg(Arg::new("filepath").required(true).help("the file to measure"))
+                        .arg(
+                            Arg::new("function")
+                                .help("only print the estimate for the function of this name"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("show")
+                        .about("print the source of a top level function")
+                        .arg(Arg::new("filepath").required(true).help("the file to read"))
+                        .arg(
+                            Arg::new("function")
+                                .required(true)
+                                .help("the name of the function"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("def")
+                        .about("print the source of the function a name refers to, following requires")
+                        .arg(Arg::new("filepath").required(true).help("the file using the name"))
+                        .arg(
+                            Arg::new("function")
+                                .required(true)
+                                .help("the name of the function, or ns.member for a required module's"),
+                        )
+                        .arg(
+                            Arg::new("preserve-indentation")
+                                .long("preserve-indentation")
+                                .action(ArgAction::SetTrue)
+                                .help("print lines with their original indentation"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("deps")
+                        .about("list the packages required by a file and the files it requires")
+                        .arg(Arg::new("filepath").required(true).help("the starting file")),
+                )
+                .subcommand(
+                    Command::new("names")
+                        .about("list every top level function name declared under a directory, once each")
+                        .arg(Arg::new("dir").required(true).help("the directory to search")),
+                )
+                .subcommand(
+                    Command::new("orphans")
+                        .about("list the files under a directory that none of the others import")
+                        .arg(Arg::new("dir").required(true).help("the directory to search"))
+                        .arg(
+                            Arg::new("exclude")
+                                .long("exclude")
+                                .value_name("GLOB")
+                                .action(ArgAction::Append)
+                                .help("never report files matching the glob, such as entry points and tests"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("rename-check")
+                        .about("report the names a rename would collide with, exiting with 1 if there are any")
+                        .arg(Arg::new("filepath").required(true).help("the file declaring the name"))
+                        .arg(Arg::new("old").required(true).help("the name to rename"))
+                        .arg(Arg::new("new").required(true).help("the name to rename it to"))
+                        .arg(
+                            Arg::new("importers")
+                                .long("importers")
+                                .value_name("DIR")
+                                .help("also check the files under the directory that import the name"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("dynamic")
+                        .about("list the calls to eval and new Function in a file")
+                        .arg(Arg::new("filepath").required(true).help("the file to list")),
+                )
+                .subcommand(
+                    Command::new("routes")
+                        .about("list the routes registered in files, as in router.get(\"/users\", getUsers)")
+                        .arg(
+                            Arg::new("filepaths")
+                                .required(true)
+                                .num_args(1..)
+                                .help("the files to list"),
+                        )
+                        .arg(
+                            Arg::new("methods")
+                                .long("methods")
+                                .value_name("METHODS")
+                                .value_delimiter(',')
+                                .help("the comma separated methods that register a route, get, post, put, patch, delete, head, options and all by default"),
+                        )
+                        .arg(
+                            Arg::new("handler-arg")
+                                .long("handler-arg")
+                                .value_name("INDEX")
+                                .value_parser(clap::value_parser!(usize))
+                                .help("the argument holding the handler counting the route as 0, the last argument by default"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("summary")
+                        .about("print a file's size, functions, imports and exports")
+                        .arg(Arg::new("filepath").required(true).help("the file to summarize"))
+                        .arg(
+                            Arg::new("importers")
+                                .long("importers")
+                                .value_name("DIR")
+                                .help("also list the files under the directory that import it"),
+                        ),
+                )
+                .subcommand(
+                    Command::new("exports")
+                        .about("list the names a file exports")
+                        .arg(Arg::new("filepath").required(true).help("the file to list")),
+                ),
+        )
         .arg(
             Arg::new("pattern")
                 .required(true)
@@ -33,11 +581,251 @@ fn main() {
                 .long("function-name")
                 .help("the starting function name"),
         )
+        .arg(
+            Arg::new("include")
+                .long("include")
+                .value_name("GLOB")
+                .action(ArgAction::Append)
+                .help("only follow required files matching the glob"),
+        )
+        .arg(
+            Arg::new("exclude")
+                .long("exclude")
+                .value_name("GLOB")
+                .action(ArgAction::Append)
+                .help("never follow required files matching the glob, takes precedence over --include"),
+        )
+        .arg(
+            Arg::new("watch")
+                .short('w')
+                .long("watch")
+                .action(ArgAction::SetTrue)
+                .help("search again whenever a file the search read changes"),
+        )
+        .arg(
+            Arg::new("count")
+                .short('c')
+                .long("count")
+                .action(ArgAction::SetTrue)
+                .conflicts_with("watch")
+                .help("only print the number of matching lines"),
+        )
+        .arg(
+            Arg::new("max-count")
+                .short('m')
+                .long("max-count")
+                .value_name("NUM")
+                .value_parser(clap::value_parser!(usize))
+                .help("stop searching after NUM matching lines"),
+        )
+        .arg(
+            Arg::new("timings")
+                .long("timings")
+                .action(ArgAction::SetTrue)
+                .help("print how long each file took to read and parse to stderr, slowest first"),
+        )
+        .arg(
+            Arg::new("changed-since")
+                .long("changed-since")
+                .value_name("REF")
+                .help("only print matches in files git reports as changed since REF, still following calls through the others"),
+        )
+        .arg(
+            Arg::new("directory-namespaces")
+                .long("directory-namespaces")
+                .action(ArgAction::SetTrue)
+                .help("resolve names a directory's index.js doesn't export to the file beside it that does"),
+        )
+        .arg(
+            Arg::new("call-like")
+                .long("call-like")
+                .value_name("KEYWORD")
+                .action(ArgAction::Append)
+                .help("also treat KEYWORD followed by parentheses as a keyword rather than a call, as if, for and return are"),
+        )
+        .arg(
+            Arg::new("skip-requires")
+                .long("skip-requires")
+                .action(ArgAction::SetTrue)
+                .help("only search the starting file, without following required files"),
+        )
+        .arg(
+            Arg::new("preserve-indentation")
+                .long("preserve-indentation")
+                .action(ArgAction::SetTrue)
+                .help("print matching lines with their original indentation"),
+        )
+        .arg(
+            Arg::new("max-line-length")
+                .long("max-line-length")
+                .value_name("BYTES")
+                .value_parser(clap::value_parser!(usize))
+                .help("omit the content of matching lines longer than this"),
+        )
+        .arg(
+            Arg::new("encoding")
+                .long("encoding")
+                .value_name("[GLOB=]ENCODING")
+                .action(ArgAction::Append)
+                .global(true)
+                .help("read files as utf-8 (the default) or another encoding such as latin1 or shift_jis, optionally only those matching the glob"),
+        )
         .get_matches();
 
+    // read by every subcommand as well as the search.
+    let mut encodings = Encodings::default();
+    let encoding_args = matches.get_many::<String>("encoding").into_iter().flatten();
+    for arg in encoding_args {
+        if let Err(e) = encodings.add(arg) {
+            logger::err(&e);
+            process::exit(1);
+        }
+    }
+    let analyzer = Analyzer::new(encodings.clone());
+
+    if let Some(("analyze", analyze)) = matches.subcommand() {
+        match analyze.subcommand() {
+            Some(("resolve", sub)) => {
+                let filepath = sub.get_one::<String>("filepath").unwrap();
+                let specifier = sub.get_one::<String>("specifier").unwrap();
+                return resolve(filepath, specifier);
+            }
+            Some(("functions", sub)) => {
+                let filepath = sub.get_one::<String>("filepath").unwrap();
+                let kind = sub.get_one::<FunctionKind>("kind").copied();
+                return list_functions(
+                    &analyzer,
+                    filepath,
+                    kind,
+                    sub.get_flag("nested"),
+                    sub.get_flag("bytes"),
+                );
+            }
+            Some(("longest", sub)) => {
+                let filepath = sub.get_one::<String>("filepath").unwrap();
+                let count = sub.get_one::<usize>("count").copied();
+                let min_lines = sub.get_one::<usize>("min-lines").copied().unwrap_or(0);
+                return list_longest_functions(&analyzer, filepath, count, min_lines);
+            }
+            Some(("complexity", sub)) => {
+                let filepath = sub.get_one::<String>("filepath").unwrap();
+                let function = sub.get_one::<String>("function").map(String::as_str);
+                return list_complexity(&analyzer, filepath, function);
+            }
+            Some(("show", sub)) => {
+                let filepath = sub.get_one::<String>("filepath").unwrap();
+                let function = sub.get_one::<String>("function").unwrap();
+                return show_function(&analyzer, filepath, function);
+            }
+            Some(("def", sub)) => {
+                let filepath = sub.get_one::<String>("filepath").unwrap();
+                let function = sub.get_one::<String>("function").unwrap();
+                let preserve_indentation = sub.get_flag("preserve-indentation");
+                return print_definition(filepath, function, preserve_indentation, &encodings);
+            }
+            Some(("deps", sub)) => {
+                let filepath = sub.get_one::<String>("filepath").unwrap();
+                return list_dependencies(&analyzer, filepath);
+            }
+            Some(("names", sub)) => {
+                let dir = sub.get_one::<String>("dir").unwrap();
+                return list_function_names(&analyzer, dir);
+            }
+            Some(("orphans", sub)) => {
+                let dir = sub.get_one::<String>("dir").unwrap();
+                let exclude = sub
+                    .get_many::<String>("exclude")
+                    .map(|v| v.cloned().collect())
+                    .unwrap_or_default();
+                return list_orphans(&analyzer, dir, &PathFilter::new(vec![], exclude));
+            }
+            Some(("dynamic", sub)) => {
+                let filepath = sub.get_one::<String>("filepath").unwrap();
+                return list_dynamic_code(&analyzer, filepath);
+            }
+            Some(("rename-check", sub)) => {
+                let filepath = sub.get_one::<String>("filepath").unwrap();
+                let old_name = sub.get_one::<String>("old").unwrap();
+                let new_name = sub.get_one::<String>("new").unwrap();
+                let importers = sub.get_one::<String>("importers").map(String::as_str);
+                return rename_check(&analyzer, filepath, old_name, new_name, importers);
+            }
+            Some(("routes", sub)) => {
+                let filepaths = sub.get_many::<String>("filepaths").unwrap().collect();
+                let methods = match sub.get_many::<String>("methods") {
+                    Some(methods) => methods.cloned().collect(),
+                    None => ROUTE_METHODS.iter().map(|m| m.to_string()).collect(),
+                };
+                let handler_arg = sub.get_one::<usize>("handler-arg").copied();
+                return list_routes(&analyzer, filepaths, methods, handler_arg);
+            }
+            Some(("summary", sub)) => {
+                let filepath = sub.get_one::<String>("filepath").unwrap();
+                let importers = sub.get_one::<String>("importers").map(String::as_str);
+                return summarize(&analyzer, filepath, importers);
+            }
+            Some(("exports", sub)) => {
+                let filepath = sub.get_one::<String>("filepath").unwrap();
+                return list_exports(&analyzer, filepath);
+            }
+            _ => unreachable!("analyze requires a subcommand"),
+        }
+    }
+
     let pattern = matches.get_one::<String>("pattern").unwrap();
     let filepath = matches.get_one::<String>("filepath").unwrap();
     let func_start = matches.get_one::<String>("function");
+    let values = |id: &str| -> Vec<String> {
+        matches
+            .get_many::<String>(id)
+            .map(|v| v.cloned().collect())
+            .unwrap_or_default()
+    };
+    let filter = PathFilter::new(values("include"), values("exclude"));
+
+    let options = Options {
+        filter,
+        encodings,
+        max_line_length: matches.get_one::<usize>("max-line-length").copied(),
+        skip_requires: matches.get_flag("skip-requires"),
+        preserve_indentation: matches.get_flag("preserve-indentation"),
+        max_results: matches.get_one::<usize>("max-count").copied(),
+        timings: matches.get_flag("timings"),
+        directory_namespaces: matches.get_flag("directory-namespaces"),
+        call_like_keywords: values("call-like"),
+        only_files: matches.get_one::<String>("changed-since").map(|git_ref| {
+            let dir = Path::new(filepath)
+                .parent()
+                .filter(|dir| !dir.as_os_str().is_empty())
+                .unwrap_or(Path::new("."));
+            match utils::changed_since(dir, git_ref) {
+                Ok(files) => files,
+                Err(e) => {
+                    logger::err(&e);
+                    process::exit(1);
+                }
+            }
+        }),
+    };
+
+    let func_start = func_start.map(|s| s.as_str());
+    if matches.get_flag("watch") {
+        watch(filepath, pattern, func_start, &options);
+    }
+
+    if matches.get_flag("count") {
+        let mut visitor = ASTVisitor::with_options(pattern, options).with_logger(Box::new(Quiet));
+        if let Err(e) = visitor.search(filepath, func_start) {
+            logger::err(&e);
+            process::exit(1);
+        }
+        print_timings(&visitor);
+        println!("{}", visitor.match_count());
+        return;
+    }
 
-    parse_file(filepath, pattern, func_start.map(|s| s.as_str()));
+    if let Err(e) = parse_file(filepath, pattern, func_start, &options) {
+        logger::err(&e);
+        process::exit(1);
+    }
 }
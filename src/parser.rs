@@ -1,32 +1,289 @@
 use crate::{
     ast::{
-        self, ASTNode, BlockStatement, CallExpression, FunctionStatement, Identifier,
-        MemberExpression, ObjectPattern, Program, VariableExpression,
+        self, ASTNode, BlockStatement, CallExpression, ExportAssignment, FunctionKind,
+        FunctionStatement, Identifier, MemberExpression, ObjectPattern, Program, ReExportStatement,
+        VariableExpression,
     },
     lexer::{self, TokenType},
 };
 
+/// Keywords that may precede the name of a class member.
+pub const CLASS_MODIFIERS: &[&str] = &[
+    "static",
+    "async",
+    "get",
+    "set",
+    "public",
+    "private",
+    "protected",
+    "readonly",
+    "override",
+];
+
+/// Keywords followed by parentheses that would otherwise parse as calls, those a parser skips
+/// unless given more with [`Parser::with_call_like_keywords`].
+pub const CALL_LIKE_KEYWORDS: &[&str] = &[
+    "if", "for", "while", "switch", "catch", "with", "function", "return",
+];
+
+/// The keywords that each add a branch to a function's cyclomatic complexity.
+pub const DECISION_KEYWORDS: &[&str] = &["if", "for", "while", "case", "catch"];
+
+/// The methods that register a route by default, as in `router.get("/users", getUsers)`.
+pub const ROUTE_METHODS: &[&str] = &[
+    "get", "post", "put", "patch", "delete", "head", "options", "all",
+];
+
+/// A call registering a handler for a route, found by [`Parser::route_sites`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct RouteSite {
+    pub method: String,
+    pub route: String,
+    /// the handler's name, dotted when it's a member such as `users.list`.
+    pub handler: String,
+}
+
 pub struct Parser {
     lexer: lexer::Lexer,
     curr_token: TokenType,
+    // functions assigned with `exports.name = function`, exported once the program is parsed.
+    member_exports: Vec<ast::Property>,
+    // extra statements produced alongside the last one, such as a class's methods.
+    pending: Vec<ASTNode>,
+    // where the statement being parsed starts, the start of any function it declares.
+    statement_byte: usize,
+    warnings: Vec<String>,
+    // names followed by parentheses that aren't calls, as in `if (`.
+    call_like_keywords: Vec<String>,
+}
+
+// the tokens of each argument of the call starting at the next token, ignoring whitespace.
+fn call_arguments(lexer: &mut lexer::Lexer) -> Option<Vec<Vec<TokenType>>> {
+    if lexer.next_token() != TokenType::OpenParen {
+        return None;
+    }
+
+    let mut args = vec![vec![]];
+    let mut depth = 0;
+    loop {
+        let token = lexer.next_token();
+        match &token {
+            TokenType::EOF => return None,
+            TokenType::Whitespace | TokenType::Newline => continue,
+            TokenType::CloseParen if depth == 0 => break,
+            TokenType::Comma if depth == 0 => {
+                args.push(vec![]);
+                continue;
+            }
+            TokenType::OpenParen | TokenType::OpenBraces => depth += 1,
+            TokenType::CloseParen | TokenType::CloseBraces => depth -= 1,
+            TokenType::CatchAll(c) if c == "[" => depth += 1,
+            TokenType::CatchAll(c) if c == "]" => depth -= 1,
+            _ => (),
+        }
+        args.last_mut().unwrap().push(token);
+    }
+
+    // a trailing comma leaves an empty last argument.
+    if args.len() > 1 && args.last().is_some_and(|a| a.is_empty()) {
+        args.pop();
+    }
+    Some(args)
 }
 
-fn exit(message: &str) -> ! {
-    eprintln!("{}", message);
-    std::process::exit(1)
+// whether a token starts the key of a class member, a name, string, number, `#private` name,
+// computed `[key]` or the `*` of a generator.
+fn is_member_key(token: &TokenType) -> bool {
+    match token {
+        TokenType::Identifier(_) | TokenType::String(_) | TokenType::Asterisk => true,
+        TokenType::CatchAll(s) => s == "#" || s == "[" || s.bytes().all(|b| b.is_ascii_digit()),
+        _ => false,
+    }
+}
+
+// whether the `Object` at the current token starts one of the two exports `object_statement`
+// parses, rather than a call such as `Object.keys(foo()).forEach(bar)`.
+fn at_object_export(lexer: &mut lexer::Lexer) -> bool {
+    let method = lexer.lookahead(2);
+    matches!(&method, TokenType::Identifier(m) if m == "defineProperty" || m == "assign")
+        && lexer.lookahead(1) == TokenType::Dot
+        && lexer.lookahead(3) == TokenType::OpenParen
+        && match lexer.lookahead(4) {
+            TokenType::Identifier(target) => target == "exports" || target == "module",
+            _ => false,
+        }
+}
+
+// the name made up by `a` or `a.b.c`, None for anything else.
+fn dotted_name(tokens: &[TokenType]) -> Option<String> {
+    let mut name = String::new();
+    for (i, token) in tokens.iter().enumerate() {
+        match token {
+            TokenType::Identifier(ident) if i % 2 == 0 => name.push_str(ident),
+            TokenType::Dot if i % 2 == 1 => name.push('.'),
+            _ => return None,
+        }
+    }
+    (tokens.len() % 2 == 1).then_some(name)
 }
 
 impl Parser {
     pub fn new(src: &str) -> Parser {
         let mut lexer = lexer::Lexer::new(src);
         let curr_token = lexer.next_token();
-        Parser { lexer, curr_token }
+        Parser {
+            lexer,
+            curr_token,
+            member_exports: vec![],
+            pending: vec![],
+            statement_byte: 0,
+            warnings: vec![],
+            call_like_keywords: CALL_LIKE_KEYWORDS.iter().map(|k| k.to_string()).collect(),
+        }
+    }
+
+    /// Also skips `keywords` followed by parentheses rather than parsing them as calls, on top
+    /// of [`CALL_LIKE_KEYWORDS`], such as the `unless (` of a macro or a dialect.
+    pub fn with_call_like_keywords(mut self, keywords: &[String]) -> Self {
+        self.call_like_keywords.extend(keywords.iter().cloned());
+        self
     }
 
     pub fn parse(&mut self) -> ASTNode {
         self.program()
     }
 
+    /// Lines calling `eval(...)` or `new Function(...)`, whose code can't be followed,
+    /// paired with which of the two they call.
+    pub fn dynamic_code_sites(&mut self) -> Vec<(usize, &'static str)> {
+        self.scan(|token, lexer, after_dot| match token {
+            TokenType::Identifier(ident) if ident == "eval" && !after_dot => {
+                (lexer.lookahead(1) == TokenType::OpenParen).then_some("eval")
+            }
+            TokenType::Identifier(ident) if ident == "new" => {
+                let callee = lexer.lookahead(1);
+                (callee == TokenType::Identifier(String::from("Function"))
+                    && lexer.lookahead(2) == TokenType::OpenParen)
+                    .then_some("new Function")
+            }
+            _ => None,
+        })
+    }
+
+    /// The branches in the source that cyclomatic complexity counts, with their lines: `if`,
+    /// `for`, `while`, `case` and `catch`, `&&`, `||` and the `?` of a conditional.
+    pub fn decision_points(&mut self) -> Vec<(usize, &'static str)> {
+        let mut prev = TokenType::EOF;
+        self.scan(move |token, lexer, after_dot| {
+            let point = match token {
+                TokenType::Identifier(ident) if !after_dot => DECISION_KEYWORDS
+                    .iter()
+                    .find(|k| **k == ident.as_str())
+                    .copied(),
+                TokenType::CatchAll(s) if (s == "&" || s == "|") && prev != *token => {
+                    let op = if s == "&" { "&&" } else { "||" };
+                    (lexer.lookahead(1) == *token).then_some(op)
+                }
+                // not `?.`, `??` or typescript's optional `name?: type`.
+                TokenType::CatchAll(s) if s == "?" && prev != *token => match lexer.lookahead(1) {
+                    TokenType::Dot | TokenType::Colon => None,
+                    TokenType::CatchAll(next) if next == "?" => None,
+                    _ => Some("?"),
+                },
+                _ => None,
+            };
+            prev = token.clone();
+            point
+        })
+    }
+
+    /// Every module specifier in the source, wherever it appears, with its line: those of
+    /// `require("...")` and `import("...")`, `import "..."` and `... from "..."`.
+    pub fn import_sites(&mut self) -> Vec<(usize, String)> {
+        self.scan(|token, lexer, after_dot| match token {
+            _ if after_dot => None,
+            TokenType::Identifier(ident) if ident == "require" || ident == "import" => {
+                match (lexer.lookahead(1), lexer.lookahead(2)) {
+                    (TokenType::OpenParen, TokenType::String(s)) => Some(s),
+                    (TokenType::String(s), _) if ident == "import" => Some(s),
+                    _ => None,
+                }
+            }
+            TokenType::Identifier(ident) if ident == "from" => match lexer.lookahead(1) {
+                TokenType::String(s) => Some(s),
+                _ => None,
+            },
+            _ => None,
+        })
+    }
+
+    /// Calls to one of `methods` on any object whose first argument is a string, paired with
+    /// the identifier passed as the argument at `handler_arg`, or as the last argument when
+    /// None. Calls whose handler isn't an identifier, such as an inline function, are skipped.
+    pub fn route_sites(
+        &mut self,
+        methods: &[String],
+        handler_arg: Option<usize>,
+    ) -> Vec<(usize, RouteSite)> {
+        self.scan(|token, lexer, after_dot| match token {
+            TokenType::Identifier(method) if after_dot && methods.contains(method) => {
+                let snapshot = lexer.cursor.clone();
+                let args = call_arguments(lexer);
+                lexer.cursor = snapshot;
+
+                let mut args = args?;
+                let route = match args.first()?.as_slice() {
+                    [TokenType::String(route)] => route.clone(),
+                    _ => return None,
+                };
+                let handler = match handler_arg {
+                    Some(i) if i < args.len() => args.swap_remove(i),
+                    Some(_) => return None,
+                    None => args.pop()?,
+                };
+                Some(RouteSite {
+                    method: method.clone(),
+                    route,
+                    handler: dotted_name(&handler)?,
+                })
+            }
+            _ => None,
+        })
+    }
+
+    // visits every token outside of comments, for reports that don't need the syntax tree.
+    fn scan<T>(
+        &mut self,
+        mut visit: impl FnMut(&TokenType, &mut lexer::Lexer, bool) -> Option<T>,
+    ) -> Vec<(usize, T)> {
+        let mut found = vec![];
+        let mut after_dot = false;
+        loop {
+            match &self.curr_token {
+                TokenType::EOF => return found,
+                TokenType::ForwardSlash => {
+                    self.parse_backslash();
+                    after_dot = false;
+                    continue;
+                }
+                token => {
+                    let line = self.lexer.cursor.line_num;
+                    if let Some(item) = visit(token, &mut self.lexer, after_dot) {
+                        found.push((line, item));
+                    }
+                }
+            }
+
+            after_dot = self.curr_token == TokenType::Dot;
+            self.advance_token();
+        }
+    }
+
+    /// Constructs that were understood but skipped, prefixed with their line number.
+    pub fn warnings(&self) -> &[String] {
+        &self.warnings
+    }
+
     // fn lookahead(&mut self, distance: usize) -> TokenType {
     //     match distance {
     //         0 => self.curr_token.clone(),
@@ -40,7 +297,7 @@ impl Parser {
 
     fn advance_token_till(&mut self, pred: impl Fn(&TokenType) -> bool) {
         loop {
-            if pred(&self.curr_token) {
+            if pred(&self.curr_token) || self.curr_token == TokenType::EOF {
                 break;
             }
             self.advance_token();
@@ -49,7 +306,7 @@ impl Parser {
 
     fn advance_token_against(&mut self, target: Vec<TokenType>) -> bool {
         for t in target {
-            if &self.curr_token != &t {
+            if self.curr_token != t {
                 return false;
             }
             self.advance_token();
@@ -60,7 +317,14 @@ impl Parser {
 
     fn program(&mut self) -> ASTNode {
         let start = self.lexer.cursor.line_num;
-        let statement_list = self.statement_list();
+        let mut statement_list = self.statement_list();
+        if !self.member_exports.is_empty() {
+            statement_list.push(ASTNode::ExportStatement(ObjectPattern {
+                properties: std::mem::take(&mut self.member_exports),
+                start,
+                end: self.lexer.cursor.line_num,
+            }));
+        }
         ASTNode::Program(Program {
             lines: Box::new(statement_list),
             start,
@@ -68,18 +332,14 @@ impl Parser {
         })
     }
 
-    fn eat(&mut self, expected_token: &TokenType) -> TokenType {
-        if self.curr_token == TokenType::EOF {
-            exit(format!("EOF").as_str());
-        }
-
-        if expected_token != &self.curr_token {
-            exit(format!("unexpected token '{}'", self.curr_token).as_str());
+    // skips the expected token, leaving anything else for the caller to make what it can of,
+    // as in `let x;` where an `=` was expected. Whether it was there.
+    fn eat(&mut self, expected_token: &TokenType) -> bool {
+        let found = expected_token == &self.curr_token;
+        if found {
+            self.advance_token();
         }
-
-        let previous_token = self.curr_token.clone();
-        self.advance_token();
-        previous_token
+        found
     }
 
     fn eat_identifier(&mut self) -> Option<String> {
@@ -100,19 +360,37 @@ impl Parser {
             if let Some(statement) = self.statement() {
                 statements.push(statement);
             }
+            statements.append(&mut self.pending);
         }
 
         statements
     }
 
     fn statement(&mut self) -> Option<ASTNode> {
+        self.statement_byte = self.lexer.token_start();
         match &self.curr_token {
             TokenType::OpenBraces => Some(self.block_statement()),
             TokenType::Identifier(ident) => match ident.as_str() {
                 "function" => self.function_expression(),
                 "const" | "var" | "let" => self.variable_statement(),
-                "module" => self.export_statement(),
-                "if" => {
+                // typescript's `import foo = require("./foo")`
+                "import" if self.lexer.lookahead(2) == TokenType::Equals => {
+                    self.variable_statement()
+                }
+                "import"
+                    if matches!(
+                        self.lexer.lookahead(1),
+                        TokenType::Identifier(_) | TokenType::OpenBraces | TokenType::Asterisk
+                    ) =>
+                {
+                    self.import_statement()
+                }
+                "module" | "exports" => self.export_statement(),
+                "export" => self.module_export_statement(),
+                "Object" if at_object_export(&mut self.lexer) => self.object_statement(),
+                "class" => self.class_statement(),
+                // parse the condition that follows as its own statements rather than a call.
+                k if self.call_like_keywords.iter().any(|c| c == k) => {
                     self.advance_token();
                     None
                 }
@@ -130,23 +408,719 @@ impl Parser {
     }
 
     fn export_statement(&mut self) -> Option<ASTNode> {
+        let start = self.lexer.cursor.line_num;
+        if !self.eat_exports_object() {
+            return None;
+        }
+
+        match &self.curr_token {
+            TokenType::Equals => {
+                self.advance_token();
+                self.export_value(start)
+            }
+            TokenType::Dot => self.member_export(start),
+            TokenType::CatchAll(s) if s == "[" => self.member_export(start),
+            _ => None,
+        }
+    }
+
+    // `export { a, b as c } [from "./m"]`, `export * from "./m"`, `export const a = b`,
+    // `export function a() {}`, `export default ...` and typescript's `export = foo`.
+    fn module_export_statement(&mut self) -> Option<ASTNode> {
+        let start = self.lexer.cursor.line_num;
+        self.advance_token();
+
+        match &self.curr_token {
+            TokenType::Identifier(keyword)
+                if matches!(keyword.as_str(), "const" | "let" | "var" | "function") =>
+            {
+                let node = match keyword.as_str() {
+                    "function" => self.function_expression()?,
+                    _ => self.variable_statement()?,
+                };
+                let name = match &node {
+                    ASTNode::FunctionStatement(fs) => Some(&fs.name),
+                    ASTNode::VariableExpression(ve) => match ve.lhs.as_ref() {
+                        ASTNode::Identifier(ident) => Some(&ident.name),
+                        _ => None,
+                    },
+                    _ => None,
+                };
+                if let Some(name) = name {
+                    self.member_exports.push(ast::Property {
+                        key: name.clone(),
+                        value: Some(name.clone()),
+                    });
+                }
+                Some(node)
+            }
+            TokenType::Identifier(keyword) if keyword == "default" => {
+                self.advance_token();
+                self.default_export(start)
+            }
+            TokenType::Equals => {
+                self.advance_token();
+                self.export_value(start)
+            }
+            TokenType::OpenBraces => {
+                let names = self.export_list();
+                let end = self.lexer.cursor.line_num;
+                match self.export_source() {
+                    Some(file) => Some(ASTNode::ReExportStatement(ReExportStatement {
+                        file,
+                        names: Some(names),
+                        start,
+                        end,
+                    })),
+                    None => Some(ASTNode::ExportStatement(ObjectPattern {
+                        properties: names,
+                        start,
+                        end,
+                    })),
+                }
+            }
+            TokenType::Asterisk => {
+                self.advance_token();
+                let file = self.export_source()?;
+                Some(ASTNode::ReExportStatement(ReExportStatement {
+                    file,
+                    names: None,
+                    start,
+                    end: self.lexer.cursor.line_num,
+                }))
+            }
+            _ => None,
+        }
+    }
+
+    // the value of `export default`, recorded as the module's assigned export: a function or
+    // class, named or not, an arrow, or an expression such as `compose(a, b)`.
+    fn default_export(&mut self, start: usize) -> Option<ASTNode> {
+        if self.curr_token == TokenType::Identifier(String::from("async")) {
+            self.advance_token();
+        }
+
+        let (node, name) = match &self.curr_token {
+            TokenType::Identifier(ident) if ident == "function" => {
+                let func = match self.lexer.lookahead(1) {
+                    TokenType::Identifier(_) => self.function_expression()?,
+                    _ => {
+                        self.advance_token();
+                        self.advance_token_till(|t| {
+                            matches!(t, TokenType::OpenParen | TokenType::OpenBraces)
+                        });
+                        self.function_body("default", FunctionKind::Declaration, start)
+                    }
+                };
+                let ASTNode::FunctionStatement(fs) = &func else {
+                    return None;
+                };
+                let name = fs.name.clone();
+                (Some(func), name)
+            }
+            TokenType::Identifier(ident) if ident == "class" => {
+                let name = match self.lexer.lookahead(1) {
+                    TokenType::Identifier(name) if name != "extends" => name,
+                    _ => String::from("default"),
+                };
+                self.class_statement();
+                (None, name)
+            }
+            TokenType::Identifier(_) if self.lexer.lookahead(1) == TokenType::Equals => (
+                Some(self.arrow_function_statement("default", start)),
+                String::from("default"),
+            ),
+            TokenType::OpenParen => (
+                Some(self.arrow_function_statement("default", start)),
+                String::from("default"),
+            ),
+            TokenType::Identifier(_) | TokenType::OpenBraces => {
+                return match self.export_value(start)? {
+                    ASTNode::ExportAssignment(ea) => {
+                        Some(ASTNode::ExportAssignment(ExportAssignment {
+                            default: true,
+                            ..ea
+                        }))
+                    }
+                    node => Some(node),
+                };
+            }
+            _ => return None,
+        };
+
+        self.pending
+            .push(ASTNode::ExportAssignment(ExportAssignment {
+                names: vec![name],
+                default: true,
+                start,
+                end: self.lexer.cursor.line_num,
+            }));
+        node
+    }
+
+    // the `{ a, b as c }` of an export, keyed by the exported name.
+    fn export_list(&mut self) -> Vec<ast::Property> {
+        let mut names = vec![];
+        self.eat(&TokenType::OpenBraces);
+
+        loop {
+            match &self.curr_token {
+                TokenType::CloseBraces => {
+                    self.advance_token();
+                    break;
+                }
+                TokenType::EOF => break,
+                TokenType::Identifier(local) => {
+                    let local = local.clone();
+                    self.advance_token();
+                    let mut exported = local.clone();
+                    if self.curr_token == TokenType::Identifier(String::from("as")) {
+                        self.advance_token();
+                        exported = self.eat_identifier().unwrap_or(exported);
+                    }
+                    names.push(ast::Property {
+                        key: exported,
+                        value: Some(local),
+                    });
+                }
+                _ => self.advance_token(),
+            }
+        }
+
+        names
+    }
+
+    // `import d, { a, b as c } from "./m"` and `import * as ns from "./m"`, read as the
+    // `const { default: d, a, b: c } = require("./m")` they bind the same names as.
+    fn import_statement(&mut self) -> Option<ASTNode> {
+        let start = self.lexer.cursor.line_num;
+        self.advance_token();
+        // typescript's `import type { A } from "./m"` only imports types.
+        if self.curr_token == TokenType::Identifier(String::from("type"))
+            && self.lexer.lookahead(1) != TokenType::Identifier(String::from("from"))
+        {
+            self.advance_token_till(|t| matches!(t, TokenType::Newline));
+            return None;
+        }
+
+        let mut namespace = None;
+        let mut properties = vec![];
+        loop {
+            match &self.curr_token {
+                TokenType::Identifier(ident) if ident == "from" => break,
+                TokenType::Identifier(ident) => {
+                    properties.push(ast::Property {
+                        key: String::from("default"),
+                        value: Some(ident.clone()),
+                    });
+                    self.advance_token();
+                }
+                TokenType::Asterisk => {
+                    self.advance_token();
+                    if !self.eat(&TokenType::Identifier(String::from("as"))) {
+                        return None;
+                    }
+                    namespace = self.eat_identifier();
+                }
+                TokenType::OpenBraces => {
+                    // the export list's `local as exported`, here `imported as local`.
+                    properties.extend(self.export_list().into_iter().map(|p| ast::Property {
+                        key: p.value.unwrap_or_default(),
+                        value: Some(p.key),
+                    }))
+                }
+                TokenType::Comma | TokenType::Newline => self.advance_token(),
+                _ => return None,
+            }
+        }
+
+        let file = self.export_source()?;
+        let end = self.lexer.cursor.line_num;
+        let lhs = match namespace {
+            Some(name) => ASTNode::Identifier(Identifier { name, start, end }),
+            None => ASTNode::ObjectPattern(ObjectPattern {
+                properties,
+                start,
+                end,
+            }),
+        };
+        let require = ASTNode::CallExpression(CallExpression {
+            base: Box::new(ASTNode::Identifier(Identifier {
+                name: String::from("require"),
+                start,
+                end,
+            })),
+            param: Some(file),
+            args: vec![],
+            start,
+            end,
+        });
+
+        Some(ASTNode::VariableExpression(VariableExpression {
+            lhs: Box::new(lhs),
+            rhs: Box::new(require),
+            start,
+            end,
+        }))
+    }
+
+    // the `from "./m"` ending an export.
+    fn export_source(&mut self) -> Option<String> {
+        if self.curr_token != TokenType::Identifier(String::from("from")) {
+            return None;
+        }
+        self.advance_token();
+
+        match &self.curr_token {
+            TokenType::String(s) => {
+                let s = s.clone();
+                self.advance_token();
+                Some(s)
+            }
+            _ => None,
+        }
+    }
+
+    // `class Name { ... }`, producing each method as a function named `Name.method`.
+    fn class_statement(&mut self) -> Option<ASTNode> {
+        self.advance_token();
+        let class = match &self.curr_token {
+            TokenType::Identifier(ident) if ident != "extends" => self.eat_identifier()?,
+            // `export default class { ... }`
+            _ => String::from("default"),
+        };
+        self.advance_token_till(|t| matches!(t, TokenType::OpenBraces | TokenType::EOF));
+        if self.curr_token != TokenType::OpenBraces {
+            return None;
+        }
+        self.advance_token();
+
+        let mut methods = vec![];
+        loop {
+            let start = self.lexer.cursor.line_num;
+            self.statement_byte = self.lexer.token_start();
+            let name = match &self.curr_token {
+                TokenType::EOF => break,
+                TokenType::CloseBraces => {
+                    self.advance_token();
+                    break;
+                }
+                TokenType::ForwardSlash => {
+                    self.parse_backslash();
+                    continue;
+                }
+                // `static`, `async`, `private` etc. when followed by the member's name.
+                TokenType::Identifier(ident)
+                    if CLASS_MODIFIERS.contains(&ident.as_str())
+                        && is_member_key(&self.lexer.lookahead(1)) =>
+                {
+                    self.advance_token();
+                    continue;
+                }
+                TokenType::Asterisk => {
+                    self.advance_token();
+                    continue;
+                }
+                t if is_member_key(t) => match self.member_key() {
+                    Some(key) => key.map(|key| format!("{}{}", class, key)),
+                    None => continue,
+                },
+                _ => {
+                    self.advance_token();
+                    continue;
+                }
+            };
+
+            // a member without a name is still parsed, to skip past it.
+            let member = match &self.curr_token {
+                TokenType::OpenParen => self.class_method(name.as_deref().unwrap_or(""), start),
+                TokenType::Equals => {
+                    self.advance_token();
+                    self.class_field(name.as_deref().unwrap_or(""), start)
+                }
+                _ => None,
+            };
+            match member {
+                Some(method) if name.is_some() => methods.push(method),
+                Some(_) => (),
+                None => self.skip_expression(|t| match t {
+                    TokenType::CatchAll(s) => s == ";",
+                    _ => matches!(t, TokenType::Newline | TokenType::CloseBraces),
+                }),
+            }
+        }
+
+        self.pending = methods;
+        None
+    }
+
+    // a method's parameters and body, None for a typescript overload or abstract method
+    // declared without one, as in `greet(name: string): void;`.
+    fn class_method(&mut self, name: &str, start: usize) -> Option<ASTNode> {
+        self.skip_params();
+        loop {
+            match &self.curr_token {
+                TokenType::OpenBraces => break,
+                TokenType::CatchAll(s) if s == ";" => return None,
+                TokenType::EOF => return None,
+                TokenType::Newline if self.lexer.lookahead(1) != TokenType::OpenBraces => {
+                    return None
+                }
+                _ => self.advance_token(),
+            }
+        }
+
+        Some(self.function_body(name, FunctionKind::Method, start))
+    }
+
+    // a function assigned to a class field, `(e) => {}`, `e => {}`, `async () => {}` or
+    // `function () {}`, None for any other value, including arrows returning an expression.
+    fn class_field(&mut self, name: &str, start: usize) -> Option<ASTNode> {
+        if self.curr_token == TokenType::Identifier(String::from("async")) {
+            self.advance_token();
+        }
+
+        match &self.curr_token {
+            TokenType::Identifier(ident) if ident == "function" => {
+                self.advance_token();
+                self.advance_token_till(|t| {
+                    matches!(t, TokenType::OpenParen | TokenType::OpenBraces)
+                });
+                return Some(self.function_body(name, FunctionKind::Method, start));
+            }
+            TokenType::OpenParen => self.skip_params(),
+            TokenType::Identifier(_) if self.lexer.lookahead(1) == TokenType::Equals => {
+                self.advance_token()
+            }
+            _ => return None,
+        }
+
+        // past any return type to the `=>`.
+        self.advance_token_till(|t| matches!(t, TokenType::Equals | TokenType::Newline));
         if !self.advance_token_against(vec![
-            TokenType::Identifier(String::from("module")),
-            TokenType::Dot,
-            TokenType::Identifier(String::from("exports")),
             TokenType::Equals,
-        ]) {
+            TokenType::CatchAll(String::from(">")),
+        ]) || self.curr_token != TokenType::OpenBraces
+        {
             return None;
         }
 
+        Some(self.arrow_function_statement(name, start))
+    }
+
+    // the key of a class member as appended to the class name: `.name`, `.#name`, `["name"]`,
+    // `[0]` or the `[Symbol.iterator]` of a computed key. None for one that isn't closed and
+    // Some(None), with a warning, for a computed key that can't be named, as `[prefix + "x"]`.
+    fn member_key(&mut self) -> Option<Option<String>> {
+        let start = self.lexer.cursor.line_num;
+        let key = match &self.curr_token {
+            TokenType::Identifier(ident) => format!(".{}", ident),
+            TokenType::String(s) => format!("[\"{}\"]", s),
+            TokenType::CatchAll(s) if s == "#" => {
+                self.advance_token();
+                return self
+                    .eat_identifier()
+                    .map(|ident| Some(format!(".#{}", ident)));
+            }
+            TokenType::CatchAll(s) if s == "[" => {
+                self.advance_token();
+                let mut tokens = vec![];
+                let mut depth = 0;
+                loop {
+                    match &self.curr_token {
+                        TokenType::EOF => return None,
+                        TokenType::CatchAll(s) if s == "]" && depth == 0 => break,
+                        TokenType::CatchAll(s) if s == "[" => depth += 1,
+                        TokenType::CatchAll(s) if s == "]" => depth -= 1,
+                        _ => (),
+                    }
+                    tokens.push(self.curr_token.clone());
+                    self.advance_token();
+                }
+                let key = match tokens.as_slice() {
+                    [TokenType::String(s)] => format!("\"{}\"", s),
+                    _ => match dotted_name(&tokens) {
+                        Some(name) => name,
+                        None => {
+                            self.advance_token();
+                            self.warnings.push(format!(
+                                "{}: skipping class member with a computed key",
+                                start + 1
+                            ));
+                            return Some(None);
+                        }
+                    },
+                };
+                format!("[{}]", key)
+            }
+            // numbers are lexed a digit at a time.
+            _ => {
+                let mut digits = String::new();
+                while let TokenType::CatchAll(s) = &self.curr_token {
+                    if !s.bytes().all(|b| b.is_ascii_digit()) {
+                        break;
+                    }
+                    digits.push_str(s);
+                    self.advance_token();
+                }
+                return Some(Some(format!("[{}]", digits)));
+            }
+        };
+        self.advance_token();
+        Some(Some(key))
+    }
+
+    // exports.name = ... or the transpiled exports["name"] = ...
+    fn member_export(&mut self, start: usize) -> Option<ASTNode> {
+        let key = if self.curr_token == TokenType::Dot {
+            self.advance_token();
+            self.eat_identifier()?
+        } else {
+            self.advance_token();
+            let key = match &self.curr_token {
+                TokenType::String(s) => s.clone(),
+                _ => {
+                    self.warnings.push(format!(
+                        "{}: skipping export with a computed key",
+                        start + 1
+                    ));
+                    return None;
+                }
+            };
+            self.advance_token();
+            if !self.advance_token_against(vec![TokenType::CatchAll(String::from("]"))]) {
+                return None;
+            }
+            key
+        };
+
+        if !self.advance_token_against(vec![TokenType::Equals]) {
+            return None;
+        }
+        self.advance_token_till(|t| t != &TokenType::Newline);
+
+        if self.curr_token == TokenType::Identifier(String::from("async")) {
+            self.advance_token();
+        }
+
+        let func = match &self.curr_token {
+            TokenType::OpenParen => self.arrow_function_statement(&key, start),
+            TokenType::Identifier(ident) if ident == "function" => {
+                self.advance_token();
+                self.eat_identifier();
+                self.function_body(&key, FunctionKind::Declaration, start)
+            }
+            // `exports.x = impl`, or `exports.x = lib.y` keeping the member's path.
+            TokenType::Identifier(ident) => {
+                let mut value = ident.clone();
+                self.advance_token();
+                while self.curr_token == TokenType::Dot {
+                    self.advance_token();
+                    value = format!("{}.{}", value, self.eat_identifier()?);
+                }
+                // the result of a call, not a name that can be followed.
+                if self.curr_token == TokenType::OpenParen {
+                    return None;
+                }
+                return Some(ASTNode::ExportStatement(ObjectPattern {
+                    properties: vec![ast::Property {
+                        key,
+                        value: Some(value),
+                    }],
+                    start,
+                    end: self.lexer.cursor.line_num,
+                }));
+            }
+            _ => return None,
+        };
+
+        self.member_exports.push(ast::Property {
+            key: key.clone(),
+            value: Some(key),
+        });
+        Some(func)
+    }
+
+    // the value assigned to module.exports
+    fn export_value(&mut self, start: usize) -> Option<ASTNode> {
         match &self.curr_token {
             TokenType::OpenBraces => {
                 Some(ASTNode::ExportStatement(self.object_pattern_expression()))
             }
+            TokenType::Identifier(ident) => self.export_assignment(ident.clone(), start),
             _ => None,
         }
     }
 
+    // exports or module.exports
+    fn eat_exports_object(&mut self) -> bool {
+        if self.curr_token == TokenType::Identifier(String::from("exports")) {
+            self.advance_token();
+            return true;
+        }
+
+        self.advance_token_against(vec![
+            TokenType::Identifier(String::from("module")),
+            TokenType::Dot,
+            TokenType::Identifier(String::from("exports")),
+        ])
+    }
+
+    // `Object.defineProperty(exports, ...)` or `Object.assign(module.exports, ...)`.
+    fn object_statement(&mut self) -> Option<ASTNode> {
+        let start = self.lexer.cursor.line_num;
+        if !self.advance_token_against(vec![
+            TokenType::Identifier(String::from("Object")),
+            TokenType::Dot,
+        ]) {
+            return None;
+        }
+
+        let method = self.eat_identifier()?;
+        if !matches!(method.as_str(), "defineProperty" | "assign")
+            || !self.advance_token_against(vec![TokenType::OpenParen])
+            || !self.eat_exports_object()
+            || !self.advance_token_against(vec![TokenType::Comma])
+        {
+            return None;
+        }
+        self.advance_token_till(|t| t != &TokenType::Newline);
+
+        let properties = match method.as_str() {
+            "assign" => self.assign_properties()?,
+            _ => self.define_property()?,
+        };
+        Some(ASTNode::ExportStatement(ObjectPattern {
+            properties,
+            start,
+            end: self.lexer.cursor.line_num,
+        }))
+    }
+
+    // the `"name", { get: function () { return impl; } }` of `Object.defineProperty(exports, ...)`.
+    fn define_property(&mut self) -> Option<Vec<ast::Property>> {
+        let key = match &self.curr_token {
+            TokenType::String(s) if s != "__esModule" => s.clone(),
+            _ => return None,
+        };
+        self.advance_token();
+
+        if !self.advance_token_against(vec![TokenType::Comma]) {
+            return None;
+        }
+
+        let value = self.property_descriptor_value().unwrap_or(key.clone());
+        Some(vec![ast::Property {
+            key,
+            value: Some(value),
+        }])
+    }
+
+    // the `{ foo, bar: impl }` of `Object.assign(module.exports, { ... })`.
+    fn assign_properties(&mut self) -> Option<Vec<ast::Property>> {
+        if self.curr_token != TokenType::OpenBraces {
+            return None;
+        }
+
+        Some(self.object_pattern_expression().properties)
+    }
+
+    // the identifier behind `value: impl` or a getter's `return impl`.
+    fn property_descriptor_value(&mut self) -> Option<String> {
+        if self.curr_token != TokenType::OpenBraces {
+            return None;
+        }
+
+        let mut depth = 0;
+        let mut value = None;
+        loop {
+            match &self.curr_token {
+                TokenType::OpenBraces => depth += 1,
+                TokenType::CloseBraces => depth -= 1,
+                TokenType::EOF => return value,
+                TokenType::Identifier(ident) if ident == "value" || ident == "return" => {
+                    if ident == "value" {
+                        self.advance_token();
+                        if self.curr_token != TokenType::Colon {
+                            continue;
+                        }
+                    }
+                    self.advance_token();
+
+                    if let Some(ident) = self.eat_identifier() {
+                        if self.curr_token != TokenType::Dot {
+                            value = Some(ident);
+                        }
+                    }
+                    continue;
+                }
+                _ => (),
+            }
+
+            self.advance_token();
+            if depth == 0 {
+                return value;
+            }
+        }
+    }
+
+    // module.exports = require("./other"), module.exports = foo or module.exports = wrap(foo)
+    fn export_assignment(&mut self, ident: String, start: usize) -> Option<ASTNode> {
+        let ce = match self.parse_identifier(ident) {
+            ASTNode::CallExpression(ce) => ce,
+            // `module.exports = __DEV__ ? devImpl : prodImpl` may export either branch.
+            ASTNode::Identifier(_) if self.curr_token == TokenType::CatchAll(String::from("?")) => {
+                self.advance_token();
+                let mut names = vec![];
+                names.extend(self.eat_identifier());
+                if self.advance_token_against(vec![TokenType::Colon]) {
+                    names.extend(self.eat_identifier());
+                }
+                return Some(ASTNode::ExportAssignment(ExportAssignment {
+                    names,
+                    default: false,
+                    start,
+                    end: self.lexer.cursor.line_num,
+                }));
+            }
+            ASTNode::Identifier(ident) => {
+                return Some(ASTNode::ExportAssignment(ExportAssignment {
+                    names: vec![ident.name],
+                    default: false,
+                    start,
+                    end: ident.end,
+                }))
+            }
+            _ => return None,
+        };
+
+        if let (ASTNode::Identifier(base), Some(file)) = (ce.base.as_ref(), &ce.param) {
+            if base.name == "require" {
+                return Some(ASTNode::ReExportStatement(ReExportStatement {
+                    file: file.clone(),
+                    names: None,
+                    start,
+                    end: ce.end,
+                }));
+            }
+        }
+
+        // connect(mapState)(Component) wraps every identifier passed along the chain.
+        let mut names = vec![];
+        let mut node = self.member_expression(ASTNode::CallExpression(ce));
+        while let ASTNode::CallExpression(ce) = node {
+            names.splice(0..0, ce.args);
+            node = *ce.base;
+        }
+
+        Some(ASTNode::ExportAssignment(ExportAssignment {
+            names,
+            default: false,
+            start,
+            end: self.lexer.cursor.line_num,
+        }))
+    }
+
     fn parse_backslash(&mut self) {
         self.advance_token();
 
@@ -154,15 +1128,12 @@ impl Parser {
             TokenType::Asterisk => loop {
                 self.advance_token_till(|t| t == &TokenType::Asterisk);
                 self.advance_token();
-                if self.curr_token == TokenType::ForwardSlash {
+                if matches!(self.curr_token, TokenType::ForwardSlash | TokenType::EOF) {
                     return;
                 }
             },
             TokenType::ForwardSlash => {
-                self.advance_token_till(|t| match t {
-                    TokenType::Newline | TokenType::EOF => true,
-                    _ => false,
-                });
+                self.advance_token_till(|t| matches!(t, TokenType::Newline | TokenType::EOF));
             }
             _ => (),
         };
@@ -174,33 +1145,40 @@ impl Parser {
         self.eat(&TokenType::OpenBraces);
 
         loop {
-            if &self.curr_token == &TokenType::Newline {
-                self.advance_token();
-            }
+            self.advance_token_till(|t| t != &TokenType::Newline);
 
-            if &self.curr_token == &TokenType::CloseBraces {
+            if self.curr_token == TokenType::CloseBraces {
                 self.advance_token();
                 break;
             }
 
-            let key = match self.eat_identifier() {
-                Some(ident) => ident,
-                None => break,
+            let key = match &self.curr_token {
+                TokenType::Identifier(s) | TokenType::String(s) => s.clone(),
+                _ => break,
             };
+            self.advance_token();
 
-            let mut value = key.clone();
+            let mut value = Some(key.clone());
 
-            if &self.curr_token == &TokenType::Colon {
+            if self.curr_token == TokenType::Colon {
                 self.advance_token();
-                if let TokenType::Identifier(ident) = &self.curr_token {
-                    value = ident.clone();
-                    self.advance_token();
-                };
+                self.advance_token_till(|t| t != &TokenType::Newline);
+                value = self.eat_identifier();
+                // anything more than a bare identifier is data rather than a function reference.
+                if !matches!(
+                    self.curr_token,
+                    TokenType::Comma | TokenType::CloseBraces | TokenType::Newline
+                ) {
+                    value = None;
+                    self.skip_expression(|t| {
+                        matches!(t, TokenType::Comma | TokenType::CloseBraces)
+                    });
+                }
             }
 
             properties.push(ast::Property { key, value });
 
-            if &self.curr_token == &TokenType::Comma {
+            if self.curr_token == TokenType::Comma {
                 self.advance_token();
             }
         }
@@ -212,16 +1190,63 @@ impl Parser {
         }
     }
 
-    fn arrow_function_statement(&mut self, name: &str) -> ASTNode {
-        let start = self.lexer.cursor.line_num;
+    // skips to the first token outside any brackets that ends the expression.
+    fn skip_expression(&mut self, end: impl Fn(&TokenType) -> bool) {
+        let mut depth = 0;
+        loop {
+            match &self.curr_token {
+                TokenType::EOF => return,
+                t if depth == 0 && end(t) => return,
+                TokenType::OpenParen | TokenType::OpenBraces => depth += 1,
+                TokenType::CloseParen | TokenType::CloseBraces => depth -= 1,
+                TokenType::CatchAll(s) if s == "[" => depth += 1,
+                TokenType::CatchAll(s) if s == "]" => depth -= 1,
+                _ => (),
+            }
+            self.advance_token();
+        }
+    }
+
+    // skips a parenthesised parameter list, default values may contain their own parens and braces.
+    fn skip_params(&mut self) {
+        if self.curr_token != TokenType::OpenParen {
+            return;
+        }
+
+        let mut depth = 0;
+        loop {
+            match self.curr_token {
+                TokenType::OpenParen => depth += 1,
+                TokenType::CloseParen => depth -= 1,
+                TokenType::EOF => return,
+                _ => (),
+            }
+            self.advance_token();
+            if depth == 0 {
+                return;
+            }
+        }
+    }
+
+    fn arrow_function_statement(&mut self, name: &str, start: usize) -> ASTNode {
+        self.function_body(name, FunctionKind::Arrow, start)
+    }
+
+    // the parameters and body following a function's name, or the `=>` arrow's parameters.
+    fn function_body(&mut self, name: &str, kind: FunctionKind, start: usize) -> ASTNode {
+        let start_byte = self.statement_byte;
+        self.skip_params();
         self.advance_token_till(|t| t == &TokenType::OpenBraces);
         let body = self.block_statement();
 
         ASTNode::FunctionStatement(FunctionStatement {
             name: String::from(name),
+            kind,
             body: Box::new(body),
             start,
             end: self.lexer.cursor.line_num,
+            start_byte,
+            end_byte: self.lexer.prev_token_end(),
         })
     }
 
@@ -235,11 +1260,28 @@ impl Parser {
             _ => return None,
         };
 
-        self.eat(&TokenType::Equals);
+        // a declaration without a value, as in `let x`, leaving what follows to the next
+        // statement.
+        if !self.eat(&TokenType::Equals) {
+            return None;
+        }
+        // formatters wrap long declarations after the `=`.
+        self.advance_token_till(|t| t != &TokenType::Newline);
+
+        if self.curr_token == TokenType::Identifier(String::from("async"))
+            && self.lexer.lookahead(1) == TokenType::OpenParen
+        {
+            self.advance_token();
+        }
 
         match &self.curr_token {
             TokenType::OpenParen => match &lhs {
-                ASTNode::Identifier(ident) => Some(self.arrow_function_statement(&ident.name)),
+                ASTNode::Identifier(ident) if self.at_wrapped_function() => {
+                    Some(self.iife_statement(&ident.name, start))
+                }
+                ASTNode::Identifier(ident) => {
+                    Some(self.arrow_function_statement(&ident.name, start))
+                }
                 _ => None,
             },
             TokenType::OpenBraces => {
@@ -259,6 +1301,42 @@ impl Parser {
         }
     }
 
+    // whether the `(` is wrapping a function, as in `(async () => {})()`, not a parameter list.
+    fn at_wrapped_function(&mut self) -> bool {
+        match self.lexer.lookahead(1) {
+            TokenType::OpenParen => true,
+            TokenType::Identifier(ident) => ident == "async" || ident == "function",
+            _ => false,
+        }
+    }
+
+    // `(async () => { ... })()` or `(function () { ... })()`, invoked where it's declared.
+    fn iife_statement(&mut self, name: &str, start: usize) -> ASTNode {
+        self.advance_token();
+        if self.curr_token == TokenType::Identifier(String::from("async")) {
+            self.advance_token();
+        }
+
+        let mut kind = FunctionKind::Arrow;
+        if self.curr_token == TokenType::Identifier(String::from("function")) {
+            self.advance_token();
+            self.eat_identifier();
+            kind = FunctionKind::Declaration;
+        }
+
+        let mut func = self.function_body(name, kind, start);
+        if self.advance_token_against(vec![TokenType::CloseParen])
+            && self.curr_token == TokenType::OpenParen
+        {
+            self.skip_params();
+            if let ASTNode::FunctionStatement(fs) = &mut func {
+                fs.kind = FunctionKind::Iife;
+            }
+        }
+
+        func
+    }
+
     fn call_expression(&mut self, base: ASTNode) -> ASTNode {
         let start = base.get_start().to_owned();
         self.eat(&TokenType::OpenParen);
@@ -269,17 +1347,43 @@ impl Parser {
             _ => None,
         };
 
-        self.advance_token_till(|t| t == &TokenType::CloseParen);
+        let mut args = vec![];
+        loop {
+            match &self.curr_token {
+                TokenType::CloseParen | TokenType::EOF => break,
+                TokenType::Identifier(ident) => {
+                    let ident = ident.clone();
+                    // a call among the arguments, as in `wrap(foo())`, is followed as a
+                    // statement of its own.
+                    if matches!(
+                        self.lexer.lookahead(1),
+                        TokenType::OpenParen | TokenType::Dot
+                    ) && !self.call_like_keywords.contains(&ident)
+                    {
+                        let arg = self.parse_identifier(ident);
+                        if matches!(arg, ASTNode::CallExpression(_)) {
+                            self.pending.push(arg);
+                        }
+                        continue;
+                    }
+
+                    self.advance_token();
+                    if matches!(self.curr_token, TokenType::Comma | TokenType::CloseParen) {
+                        args.push(ident);
+                    }
+                }
+                _ => self.advance_token(),
+            }
+        }
         self.eat(&TokenType::CloseParen);
 
-        let call_expression = ASTNode::CallExpression(CallExpression {
+        ASTNode::CallExpression(CallExpression {
             base: Box::new(base),
             param,
+            args,
             start,
             end: self.lexer.cursor.line_num,
-        });
-
-        call_expression
+        })
     }
 
     fn parse_identifier(&mut self, ident: String) -> ASTNode {
@@ -312,23 +1416,23 @@ impl Parser {
     }
 
     fn member_prefix_expression(&mut self, base: ASTNode) -> (ASTNode, bool) {
-        let expression = match &self.curr_token {
-            &TokenType::Dot => {
+        let expression = match self.curr_token {
+            TokenType::Dot => {
                 self.eat(&TokenType::Dot);
                 let property = match self.eat_identifier() {
                     Some(ident) => ident,
                     None => return (base, false),
                 };
                 let me = MemberExpression {
+                    start: base.get_start(),
+                    end: self.lexer.cursor.line_num,
                     base: Box::new(base),
                     property,
-                    start: 0,
-                    end: 0,
                 };
 
                 ASTNode::MemberExpression(me)
             }
-            &TokenType::OpenParen => self.call_expression(base),
+            TokenType::OpenParen => self.call_expression(base),
             _ => return (base, false),
         };
 
@@ -337,10 +1441,11 @@ impl Parser {
 
     fn block_body(&mut self) -> Vec<ASTNode> {
         let mut statements = vec![];
-        while self.curr_token != TokenType::CloseBraces {
+        while !matches!(self.curr_token, TokenType::CloseBraces | TokenType::EOF) {
             if let Some(statement) = self.statement() {
                 statements.push(statement);
             }
+            statements.append(&mut self.pending);
         }
 
         statements
@@ -362,18 +1467,8 @@ impl Parser {
     fn function_expression(&mut self) -> Option<ASTNode> {
         let start = self.lexer.cursor.line_num;
         self.advance_token();
-        let name = match self.eat_identifier() {
-            Some(ident) => ident,
-            None => return None,
-        };
-        self.advance_token_till(|t| t == &TokenType::OpenBraces);
-        let body = self.block_statement();
-
-        Some(ASTNode::FunctionStatement(FunctionStatement {
-            name,
-            body: Box::new(body),
-            start,
-            end: self.lexer.cursor.line_num,
-        }))
+        let name = self.eat_identifier()?;
+        self.advance_token_till(|t| matches!(t, TokenType::OpenParen | TokenType::OpenBraces));
+        Some(self.function_body(&name, FunctionKind::Declaration, start))
     }
 }
@@ -1,5 +1,10 @@
-use std::path::Path;
-use std::{fs, io, process};
+use crate::{encoding::Encoding, logger::Log};
+use std::collections::HashSet;
+use std::io::BufRead;
+use std::ops::RangeInclusive;
+use std::path::{Component, Path, PathBuf};
+use std::time::SystemTime;
+use std::{env, fs, io, process};
 
 pub struct OptionIterator<I> {
     pub iter: Option<I>,
@@ -24,12 +29,38 @@ impl<I> OptionIterator<I> {
     }
 }
 
+/// Lazily reads the given range of a file's lines, the file closed once the iterator is
+/// dropped. Yields nothing when there is no range or the file can't be opened.
+pub fn read_lines(
+    path: &str,
+    range: Option<RangeInclusive<usize>>,
+    encoding: Encoding,
+) -> OptionIterator<impl Iterator<Item = String>> {
+    let iter = range
+        .zip(fs::File::open(path).ok())
+        .map(move |(range, file)| {
+            io::BufReader::new(file)
+                .split(b'\n')
+                .map_while(Result::ok)
+                .skip(*range.start())
+                .take(range.end() + 1 - range.start())
+                .map(move |mut line| {
+                    if line.last() == Some(&b'\r') {
+                        line.pop();
+                    }
+                    encoding.decode_lossy(line)
+                })
+        });
+
+    OptionIterator::new(iter)
+}
+
 pub fn path_exists(path: &str) -> bool {
-    if let Err(_) = fs::metadata(path) {
+    if fs::metadata(path).is_err() {
         return false;
     }
 
-    return true;
+    true
 }
 
 pub fn is_file(path: &str) -> bool {
@@ -39,6 +70,10 @@ pub fn is_file(path: &str) -> bool {
     }
 }
 
+pub fn modified_time(path: &str) -> Option<SystemTime> {
+    fs::metadata(path).and_then(|m| m.modified()).ok()
+}
+
 pub fn get_absolute_path(path: &str) -> io::Result<String> {
     match Path::new(path).canonicalize() {
         Ok(pb) => Ok(pb.display().to_string()),
@@ -46,25 +81,339 @@ pub fn get_absolute_path(path: &str) -> io::Result<String> {
     }
 }
 
-pub fn join_path(base: &str, with: &str) -> Option<String> {
-    let mut with = with.trim_start_matches("./").to_string();
-    if Path::new(&with).extension().is_none() {
-        with.push_str(".js");
+pub fn is_relative_path(path: &str) -> bool {
+    path == "." || path == ".." || path.starts_with("./") || path.starts_with("../")
+}
+
+/// Resolves a require specifier against the file it appears in, explaining why when it can't.
+pub fn resolve_require(base: &str, specifier: &str) -> Result<String, String> {
+    resolve_require_candidates(base, specifier).map(|mut candidates| candidates.swap_remove(0))
+}
+
+/// Every file the specifier could refer to, in the order they're preferred: `.js`, `.ts`,
+/// `.mts`, `.cts`, `.json` then a directory's index.js. More than one means the require is
+/// ambiguous.
+pub fn resolve_require_candidates(base: &str, specifier: &str) -> Result<Vec<String>, String> {
+    if !is_relative_path(specifier) {
+        return resolve_package(base, specifier);
+    }
+
+    let target = normalize_path(&Path::new(base).parent().unwrap().join(specifier));
+    resolve_target(&target, specifier)
+}
+
+/// Collapses `.` and `..` segments without touching the file system, as node does, so
+/// `./missing/../foo` resolves even though `missing` doesn't exist.
+pub fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir => {
+                if !normalized.pop() {
+                    normalized.push("..");
+                }
+            }
+            c => normalized.push(c),
+        }
+    }
+    normalized
+}
+
+/// Splits a bare specifier into its package name and the path within the package,
+/// scoped packages like `@scope/pkg` spanning two segments.
+pub fn split_package_specifier(specifier: &str) -> (&str, Option<&str>) {
+    let scoped = specifier.starts_with('@');
+    match specifier.match_indices('/').nth(scoped as usize) {
+        Some((i, _)) => (&specifier[..i], Some(&specifier[i + 1..])),
+        None => (specifier, None),
+    }
+}
+
+const NODE_BUILTINS: &[&str] = &[
+    "assert",
+    "buffer",
+    "child_process",
+    "cluster",
+    "crypto",
+    "dgram",
+    "dns",
+    "events",
+    "fs",
+    "http",
+    "http2",
+    "https",
+    "net",
+    "os",
+    "path",
+    "perf_hooks",
+    "process",
+    "querystring",
+    "readline",
+    "stream",
+    "string_decoder",
+    "timers",
+    "tls",
+    "tty",
+    "url",
+    "util",
+    "v8",
+    "vm",
+    "worker_threads",
+    "zlib",
+];
+
+/// Whether the specifier names one of node's own modules, like `fs` or `node:path`.
+pub fn is_node_builtin(specifier: &str) -> bool {
+    specifier.starts_with("node:") || NODE_BUILTINS.contains(&split_package_specifier(specifier).0)
+}
+
+// the conditions of a package.json "exports" entry that require matches, most preferred first.
+const EXPORT_CONDITIONS: &[&str] = &["require", "node", "default"];
+
+// looks for the package in the node_modules of each directory above base. Only workspace
+// packages, symlinked in from outside node_modules, are followed to their real source;
+// installed packages are left as external.
+fn resolve_package(base: &str, specifier: &str) -> Result<Vec<String>, String> {
+    let (name, subpath) = split_package_specifier(specifier);
+
+    for dir in Path::new(base).ancestors().skip(1) {
+        let package = dir.join("node_modules").join(name);
+        if !package.is_dir() {
+            continue;
+        }
+
+        let installed = package.canonicalize().map_or(true, |real| {
+            real.components().any(|c| c.as_os_str() == "node_modules")
+        });
+        if installed {
+            return Err(format!(
+                "'{}' is an external package installed in node_modules",
+                specifier
+            ));
+        }
+
+        let manifest = fs::read_to_string(package.join("package.json"))
+            .ok()
+            .and_then(|json| serde_json::from_str::<serde_json::Value>(&json).ok());
+        let key = subpath.map_or(String::from("."), |subpath| format!("./{}", subpath));
+        if let Some(target) = manifest.as_ref().and_then(|m| package_export(m, &key)) {
+            return resolve_target(&normalize_path(&package.join(target)), specifier);
+        }
+
+        return match subpath {
+            Some(subpath) => resolve_target(&normalize_path(&package.join(subpath)), specifier),
+            None => {
+                let main = manifest.as_ref().and_then(|m| m.get("main")?.as_str());
+                resolve_target(&package.join(main.unwrap_or("index.js")), specifier)
+            }
+        };
+    }
+
+    Err(format!(
+        "'{}' is an external package not found in any node_modules",
+        specifier
+    ))
+}
+
+// the file a package.json's "exports" field maps key, `.` or a `./subpath`, to.
+fn package_export<'a>(manifest: &'a serde_json::Value, key: &str) -> Option<&'a str> {
+    let exports = manifest.get("exports")?;
+    // without any `./` keys, the field describes the entry point alone.
+    let entry = match exports.as_object() {
+        Some(map) if map.keys().any(|k| k.starts_with('.')) => map.get(key)?,
+        _ if key == "." => exports,
+        _ => return None,
+    };
+    export_target(entry)
+}
+
+// a string target, or the target of the most preferred condition require matches.
+fn export_target(entry: &serde_json::Value) -> Option<&str> {
+    match entry {
+        serde_json::Value::String(target) => Some(target),
+        serde_json::Value::Object(conditions) => EXPORT_CONDITIONS
+            .iter()
+            .find_map(|c| export_target(conditions.get(*c)?)),
+        serde_json::Value::Array(targets) => targets.iter().find_map(export_target),
+        _ => None,
     }
+}
 
-    if let Ok(pb) = Path::new(base).parent().unwrap().join(with).canonicalize() {
-        return Some(pb.display().to_string());
+fn resolve_target(target: &Path, specifier: &str) -> Result<Vec<String>, String> {
+    // `.`, `..` and `./dir/` name a directory, resolved to its index.js.
+    let is_dir = specifier.ends_with('/') || Path::new(specifier).file_name().is_none();
+    let candidates = if is_dir {
+        vec![target.join("index.js")]
+    } else if target.extension().is_none() {
+        vec![
+            PathBuf::from(format!("{}.js", target.display())),
+            PathBuf::from(format!("{}.ts", target.display())),
+            PathBuf::from(format!("{}.mts", target.display())),
+            PathBuf::from(format!("{}.cts", target.display())),
+            PathBuf::from(format!("{}.json", target.display())),
+            target.join("index.js"),
+        ]
+    } else {
+        // typescript sources import `./m.mjs` and `./c.cjs` by the names they compile to.
+        let source = match target.extension().and_then(|ext| ext.to_str()) {
+            Some("mjs") => Some(target.with_extension("mts")),
+            Some("cjs") => Some(target.with_extension("cts")),
+            _ => None,
+        };
+        std::iter::once(target.to_path_buf())
+            .chain(source)
+            .collect()
     };
 
-    None
+    let found: Vec<String> = candidates
+        .iter()
+        .filter_map(|c| c.canonicalize().ok())
+        .filter(|pb| pb.is_file())
+        .map(|pb| pb.display().to_string())
+        .collect();
+    if !found.is_empty() {
+        return Ok(found);
+    }
+
+    match is_dir || target.extension().is_some() {
+        true => Err(format!(
+            "failed to resolve {}: no such file",
+            candidates[0].display()
+        )),
+        false => Err(format!(
+            "no file matching '{}' with extension .js, .ts, .mts, .cts or .json or a directory index.js",
+            specifier
+        )),
+    }
+}
+
+/// The extensions of the files searched for code, `.mts` being an ES module and `.cts` a
+/// CommonJS one, though both forms of import are followed in any of them.
+pub const SOURCE_EXTENSIONS: &[&str] = &["js", "ts", "mts", "cts"];
+
+/// Whether the path names a source file by its extension.
+pub fn is_source_file(path: &str) -> bool {
+    Path::new(path)
+        .extension()
+        .is_some_and(|ext| SOURCE_EXTENSIONS.iter().any(|s| ext == *s))
+}
+
+/// Whether a resolved require is a JSON file, data rather than code to follow.
+pub fn is_json(path: &str) -> bool {
+    path.ends_with(".json")
+}
+
+/// The absolute paths of the source files git reports as changed since `git_ref`, or as
+/// untracked and not ignored, in the working tree of the repository containing `dir`.
+pub fn changed_since(dir: &Path, git_ref: &str) -> Result<HashSet<String>, String> {
+    let git = |args: &[&str]| -> Result<String, String> {
+        let out = process::Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(args)
+            .output()
+            .map_err(|e| format!("failed to run git: {}", e))?;
+        match out.status.success() {
+            true => Ok(String::from_utf8_lossy(&out.stdout).into_owned()),
+            false => Err(String::from_utf8_lossy(&out.stderr).trim().to_string()),
+        }
+    };
+
+    let root = git(&["rev-parse", "--show-toplevel"])
+        .map_err(|_| format!("{} is not inside a git repository", dir.display()))?;
+    // both relative to the root, whatever diff.relative is set to and wherever dir is.
+    let changed = git(&["diff", "--name-only", "--no-relative", git_ref, "--"])?;
+    let untracked = git(&[
+        "ls-files",
+        "--others",
+        "--exclude-standard",
+        "--full-name",
+        "--",
+        ":/",
+    ])?;
+
+    Ok(changed
+        .lines()
+        .chain(untracked.lines())
+        .filter(|path| is_source_file(path))
+        .filter_map(|path| {
+            get_absolute_path(&Path::new(root.trim()).join(path).to_string_lossy()).ok()
+        })
+        .collect())
+}
+
+pub fn join_path(base: &str, with: &str) -> Option<String> {
+    resolve_require(base, with).ok()
 }
 
-pub fn read_file(filename: &str) -> String {
-    match fs::read_to_string(filename) {
-        Ok(s) => s,
+/// Reads and decodes a file, reporting a failure to `logger` before returning it.
+pub fn read_file(
+    filename: &str,
+    encoding: Encoding,
+    logger: &dyn Log,
+) -> Result<String, io::Error> {
+    match fs::read(filename) {
+        Ok(bytes) => Ok(encoding.decode(filename, bytes, logger)),
         Err(err) => {
-            eprintln!("failed to read file {}: {}", filename, err.to_string());
-            process::exit(1);
+            logger.err(&format!("failed to read file {}: {}", filename, err));
+            Err(err)
         }
     }
 }
+
+// `*` and `?` stay within a path segment, `**` spans any number of segments.
+pub fn glob_match(pattern: &str, path: &str) -> bool {
+    fn glob(p: &[u8], s: &[u8]) -> bool {
+        match p {
+            [] => s.is_empty(),
+            [b'*', b'*', b'/', rest @ ..] => (0..=s.len())
+                .filter(|&i| i == 0 || s[i - 1] == b'/')
+                .any(|i| glob(rest, &s[i..])),
+            [b'*', b'*', rest @ ..] => (0..=s.len()).any(|i| glob(rest, &s[i..])),
+            [b'*', rest @ ..] => (0..=s.len())
+                .take_while(|&i| i == 0 || s[i - 1] != b'/')
+                .any(|i| glob(rest, &s[i..])),
+            [b'?', rest @ ..] => matches!(s.first(), Some(c) if *c != b'/') && glob(rest, &s[1..]),
+            [c, rest @ ..] => s.first() == Some(c) && glob(rest, &s[1..]),
+        }
+    }
+
+    glob(pattern.as_bytes(), path.as_bytes())
+}
+
+/// Matches the glob against the path relative to the working directory as well as the absolute path.
+pub fn path_matches(glob: &str, path: &str) -> bool {
+    let relative = match env::current_dir() {
+        Ok(cwd) => match Path::new(path).strip_prefix(cwd) {
+            Ok(p) => p.display().to_string(),
+            Err(_) => path.to_string(),
+        },
+        Err(_) => path.to_string(),
+    };
+
+    glob_match(glob, &relative) || glob_match(glob, path)
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct PathFilter {
+    pub include: Vec<String>,
+    pub exclude: Vec<String>,
+}
+
+impl PathFilter {
+    pub fn new(include: Vec<String>, exclude: Vec<String>) -> Self {
+        Self { include, exclude }
+    }
+
+    /// Excludes take precedence over includes.
+    pub fn allows(&self, path: &str) -> bool {
+        let matches_any = |globs: &Vec<String>| globs.iter().any(|g| path_matches(g, path));
+
+        if matches_any(&self.exclude) {
+            return false;
+        }
+
+        self.include.is_empty() || matches_any(&self.include)
+    }
+}
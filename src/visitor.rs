@@ -1,9 +1,15 @@
-use std::collections::{HashMap, HashSet};
+use std::{
+    collections::{HashMap, HashSet},
+    fs, io,
+    path::Path,
+    time::{Duration, Instant},
+};
 
 use crate::{
-    ast::{ASTNode, BlockStatement, CallExpression, FunctionStatement, Program},
+    ast::{ASTNode, BlockStatement, CallExpression, FunctionKind, FunctionStatement, Program},
+    encoding::Encodings,
     file_scope::{ProgramScope, Symbol},
-    logger,
+    logger::{Log, Terminal},
     parser::Parser,
     utils,
 };
@@ -14,36 +20,231 @@ struct File {
     ast: ASTNode,
     lines: Vec<String>,
     lines_recorded: HashSet<usize>,
+    timing: Option<FileTiming>,
+}
+
+/// How long reading and parsing a file took.
+#[derive(Debug, Clone)]
+pub struct FileTiming {
+    pub path: String,
+    pub read: Duration,
+    pub parse: Duration,
 }
 
 impl File {
-    fn new(path: &str) -> Self {
-        let src = utils::read_file(path);
-        let ast: ASTNode = Parser::new(&src).parse();
+    fn new(path: &str, options: &Options, logger: &dyn Log) -> io::Result<Self> {
+        let started = Instant::now();
+        let src = utils::read_file(path, options.encodings.for_path(path), logger)?;
+        let read = started.elapsed();
+
+        let mut parser = Parser::new(&src).with_call_like_keywords(&options.call_like_keywords);
+        let ast: ASTNode = parser.parse();
+        for warning in parser.warnings() {
+            logger.warn(&format!("{}:{}", path, warning));
+        }
 
-        Self {
+        let timing = options.timings.then(|| FileTiming {
+            path: String::from(path),
+            read,
+            parse: started.elapsed() - read,
+        });
+
+        Ok(Self {
             path: String::from(path),
             ast,
             lines: src.split("\n").map(|s| s.to_string()).collect(),
             lines_recorded: HashSet::new(),
+            timing,
+        })
+    }
+
+    // a file that couldn't be read, searched as if it were empty.
+    fn unreadable(path: &str) -> Self {
+        Self {
+            path: String::from(path),
+            ast: Parser::new("").parse(),
+            lines: vec![String::new()],
+            lines_recorded: HashSet::new(),
+            timing: None,
         }
     }
 }
 
+// the other sources in the directory of an `index.js` or `index.ts`, none for other files.
+fn index_siblings(path: &str) -> Vec<String> {
+    let path = Path::new(path);
+    let (Some(stem), Some(dir)) = (path.file_stem(), path.parent()) else {
+        return vec![];
+    };
+    if stem != "index" {
+        return vec![];
+    }
+
+    let Ok(entries) = fs::read_dir(dir) else {
+        return vec![];
+    };
+    let mut siblings: Vec<String> = entries
+        .filter_map(Result::ok)
+        .map(|e| e.path())
+        .filter(|p| p != path && p.is_file())
+        .map(|p| p.display().to_string())
+        .filter(|p| utils::is_source_file(p))
+        .collect();
+    siblings.sort();
+    siblings
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct Options {
+    pub filter: utils::PathFilter,
+    pub encodings: Encodings,
+    /// matching lines longer than this are reported without their content.
+    pub max_line_length: Option<usize>,
+    /// only search the starting file, without reading or resolving any required files.
+    pub skip_requires: bool,
+    /// print matching lines with their original indentation rather than trimmed.
+    pub preserve_indentation: bool,
+    /// stop searching once this many matching lines have been reported.
+    pub max_results: Option<usize>,
+    /// record how long each file took to read and parse, see `ASTVisitor::timings`.
+    pub timings: bool,
+    /// only report matches in these files, calls are still followed through the others.
+    pub only_files: Option<HashSet<String>>,
+    /// resolve a name a directory's index doesn't export to the sibling of the index that
+    /// does, for barrels built in ways that can't be followed such as `...require("./a")`.
+    pub directory_namespaces: bool,
+    /// names followed by parentheses that aren't calls, on top of `CALL_LIKE_KEYWORDS`.
+    pub call_like_keywords: Vec<String>,
+}
+
 pub struct ASTVisitor {
     needle: String,
     line_num: usize,
     files: HashMap<String, File>,
     scope: ProgramScope,
+    options: Options,
+    logger: Box<dyn Log>,
+    match_count: usize,
+    warned: HashSet<String>,
+    // the functions being visited, by file and line, so recursive calls aren't followed.
+    active: HashSet<(String, usize)>,
 }
 
 impl ASTVisitor {
     pub fn new(needle: &str) -> Self {
+        Self::with_options(needle, Options::default())
+    }
+
+    pub fn with_options(needle: &str, options: Options) -> Self {
         ASTVisitor {
             line_num: 0,
             needle: String::from(needle),
             files: HashMap::new(),
             scope: ProgramScope::new(),
+            options,
+            logger: Box::new(Terminal),
+            match_count: 0,
+            warned: HashSet::new(),
+            active: HashSet::new(),
+        }
+    }
+
+    /// Routes matches and warnings to `logger` instead of the terminal.
+    pub fn with_logger(mut self, logger: Box<dyn Log>) -> Self {
+        self.logger = logger;
+        self
+    }
+
+    /// The number of matching lines reported so far.
+    pub fn match_count(&self) -> usize {
+        self.match_count
+    }
+
+    /// The recorded read and parse times of each file, slowest first, when
+    /// `Options::timings` is set.
+    pub fn timings(&self) -> Vec<FileTiming> {
+        let mut timings: Vec<FileTiming> = self
+            .files
+            .values()
+            .filter_map(|f| f.timing.clone())
+            .collect();
+        timings.sort_by_key(|t| std::cmp::Reverse(t.read + t.parse));
+        timings
+    }
+
+    pub fn files_read(&self) -> Vec<String> {
+        let mut files: Vec<String> = self.files.keys().cloned().collect();
+        files.sort();
+        files
+    }
+
+    /// The functions `name` refers to at the top level of the file at `path`, declared there or
+    /// imported from another file. `ns.member` and `ns.sub.member` name a member of a required
+    /// module, followed as calls to them are. A default export such as
+    /// `module.exports = cond ? a : b` refers to each of the functions it could be.
+    pub fn definition_of(&mut self, path: &str, name: &str) -> Vec<Symbol> {
+        let symbol = self.find_definition(path, name);
+        symbol
+            .map(|s| self.expand_assigned_export(s, &mut HashSet::new()))
+            .unwrap_or_default()
+    }
+
+    fn find_definition(&mut self, path: &str, name: &str) -> Option<Symbol> {
+        let path = utils::get_absolute_path(path).ok()?;
+        let ast = self.load_file(&path).ast.clone();
+
+        let mut visited = HashSet::new();
+        if let Some(symbol) = self.resolve_binding(&path, &ast, name, &mut visited) {
+            return Some(symbol);
+        }
+
+        let mut segments: Vec<&str> = name.split('.').collect();
+        let member = segments.pop()?;
+        let (root, namespaces) = segments.split_first()?;
+        let (specifier, ve) = ast.find_binding(root)?.try_get_require_file()?;
+        if !matches!(ve.lhs.as_ref(), ASTNode::Identifier(_)) {
+            return None;
+        }
+        let required_file = self.followable(&path, &specifier)?;
+
+        let mut module = self.module_symbol(&required_file)?;
+        for namespace in namespaces {
+            module = self.resolve_namespace(&module, namespace, &mut HashSet::new())?;
+        }
+        self.resolve_export(&module.file_path, member, &mut HashSet::new())
+    }
+
+    // the functions an assigned export names, each resolved in the file that assigns it.
+    fn expand_assigned_export(
+        &mut self,
+        symbol: Symbol,
+        visited: &mut HashSet<(String, String)>,
+    ) -> Vec<Symbol> {
+        let ASTNode::ExportAssignment(ea) = &symbol.node else {
+            return vec![symbol];
+        };
+
+        let ast = self.load_file(&symbol.file_path).ast.clone();
+        let mut symbols = vec![];
+        for name in &ea.names {
+            if let Some(found) = self.resolve_binding(&symbol.file_path, &ast, name, visited) {
+                symbols.extend(self.expand_assigned_export(found, visited));
+            }
+        }
+        symbols
+    }
+
+    fn limit_reached(&self) -> bool {
+        self.options
+            .max_results
+            .is_some_and(|max| self.match_count >= max)
+    }
+
+    // files are indexed again each time a call enters them, so repeat warnings are dropped.
+    fn warn_once(&mut self, message: String) {
+        if !self.warned.contains(&message) {
+            self.logger.warn(&message);
+            self.warned.insert(message);
         }
     }
 
@@ -53,29 +254,57 @@ impl ASTVisitor {
             None => return,
         };
 
+        if self
+            .options
+            .only_files
+            .as_ref()
+            .is_some_and(|files| !files.contains(file_path))
+        {
+            return;
+        }
+
         let file = self.files.get_mut(file_path).unwrap();
 
         for line in from..=until {
+            if self
+                .options
+                .max_results
+                .is_some_and(|max| self.match_count >= max)
+            {
+                return;
+            }
+
             if !file.lines_recorded.contains(&line) && file.lines[line].contains(&self.needle) {
-                logger::info(format!("{}: {}", line + 1, file.lines[line].trim()).as_str());
+                let content = match self.options.preserve_indentation {
+                    true => file.lines[line].trim_end(),
+                    false => file.lines[line].trim(),
+                };
+                let message = match self.options.max_line_length {
+                    Some(max) if content.len() > max => {
+                        format!("{}: [omitted long line, {} bytes]", line + 1, content.len())
+                    }
+                    _ => format!("{}: {}", line + 1, content),
+                };
+                self.logger.info(&message);
+                self.match_count += 1;
                 file.lines_recorded.insert(line);
             }
         }
     }
 
     fn push_file_scope(&mut self, file_path: &str) -> bool {
-        self.scope.push_file(file_path);
         let ast = match self.files.get(file_path) {
             Some(f) => f.ast.clone(),
             None => return false,
         };
+        self.scope.push_file(file_path);
 
         match &ast {
             ASTNode::Program(prog) => self.index_block(&prog.lines),
             _ => return false,
         };
 
-        return true;
+        true
     }
 
     fn init_visit(&mut self, node: &ASTNode) {
@@ -86,11 +315,14 @@ impl ASTVisitor {
     pub fn init_search(&mut self, path: &str) -> Result<ASTNode, String> {
         let path = match utils::get_absolute_path(path) {
             Ok(pb) => pb,
-            Err(e) => return Err(e.to_string()),
+            Err(e) => return Err(format!("file {} not found: {}", path, e)),
         };
 
         let mut files = HashMap::new();
-        files.insert(path.clone(), File::new(&path));
+        files.insert(
+            path.clone(),
+            File::new(&path, &self.options, self.logger.as_ref()).map_err(|e| e.to_string())?,
+        );
         self.files = files;
 
         self.scope.push_file(&path);
@@ -104,7 +336,7 @@ impl ASTVisitor {
             Some(func_start_name) => {
                 match &file_ast {
                     ASTNode::Program(prog) => self.index_block(&prog.lines),
-                    _ => return Err(format!("program not found")),
+                    _ => return Err("program not found".to_string()),
                 };
 
                 match file_ast.find_function(func_start_name) {
@@ -119,18 +351,24 @@ impl ASTVisitor {
     }
 
     fn visit_node(&mut self, node: &ASTNode) {
+        if self.limit_reached() {
+            return;
+        }
+
         let start = node.get_start();
         self.grep(self.line_num, start);
         self.line_num = start;
 
         match node {
-            ASTNode::BlockStatement(bs) => self.visit_block_statement(&bs),
+            ASTNode::BlockStatement(bs) => self.visit_block_statement(bs),
             ASTNode::CallExpression(ce) => self.visit_call_expression(ce),
             ASTNode::Identifier(_) => (),
-            ASTNode::FunctionStatement(fs) => self.visit_function(&fs),
+            ASTNode::FunctionStatement(fs) => self.visit_function(fs),
             ASTNode::Program(prog) => self.visit_prog(prog),
 
             ASTNode::ExportStatement(_) => (),
+            ASTNode::ReExportStatement(_) => (),
+            ASTNode::ExportAssignment(_) => (),
             ASTNode::VariableExpression(_) => (),
             ASTNode::MemberExpression(_) => (),
             ASTNode::ObjectPattern(_) => (),
@@ -150,63 +388,332 @@ impl ASTVisitor {
         }
     }
 
-    fn index_export(&mut self, required_file: &str, lhs: &ASTNode) {
-        let file = match self.files.get(required_file) {
-            Some(file) => &file,
-            None => {
-                self.files
-                    .insert(String::from(required_file), File::new(required_file));
-                self.files.get(required_file).unwrap()
+    fn load_file(&mut self, path: &str) -> &File {
+        if !self.files.contains_key(path) {
+            self.files.insert(
+                String::from(path),
+                // already reported by read_file, the search goes on without it.
+                File::new(path, &self.options, self.logger.as_ref())
+                    .unwrap_or_else(|_| File::unreadable(path)),
+            );
+        }
+
+        self.files.get(path).unwrap()
+    }
+
+    // the file `specifier`, required from the file at `from`, resolves to, or None when it
+    // doesn't resolve or searches don't follow it there. The one place the rules are applied.
+    fn followable(&self, from: &str, specifier: &str) -> Option<String> {
+        utils::join_path(from, specifier).filter(|p| self.is_followable(p))
+    }
+
+    // whether searches follow requires into the file at `path`: not json, which holds no
+    // functions, and allowed by the --include and --exclude globs.
+    fn is_followable(&self, path: &str) -> bool {
+        !utils::is_json(path) && self.options.filter.allows(path)
+    }
+
+    // follow `module.exports = require(...)` until reaching the file that declares the exports.
+    fn resolve_export_file(&mut self, path: &str) -> String {
+        let mut path = String::from(path);
+        let mut visited = HashSet::new();
+
+        while visited.insert(path.clone()) {
+            let re_export = match self.load_file(&path).ast.find_re_export() {
+                Some(re) => re.file.clone(),
+                None => break,
+            };
+
+            match self.followable(&path, &re_export) {
+                Some(full_path) => path = full_path,
+                None => break,
             }
-        };
+        }
+
+        path
+    }
+
+    // the function a module exports as `name`, following re-exports into the files they name.
+    fn resolve_export(
+        &mut self,
+        path: &str,
+        name: &str,
+        visited: &mut HashSet<(String, String)>,
+    ) -> Option<Symbol> {
+        if !visited.insert((path.to_string(), name.to_string())) {
+            return None;
+        }
+
+        let ast = self.load_file(path).ast.clone();
+        if let Some(func) = ast.find_exported_func(name) {
+            return Some(Symbol {
+                node: func.clone(),
+                file_path: path.to_string(),
+            });
+        }
+        // `export { foo as default }` importing a module that assigns module.exports, called as
+        // whichever of the functions it names, `cond ? a : b` or `compose(a, b)`, it could be.
+        if name == "default" {
+            if let Some(ea) = ast.find_assigned_export() {
+                return Some(Symbol {
+                    node: ASTNode::ExportAssignment(ea),
+                    file_path: path.to_string(),
+                });
+            }
+        }
+
+        // exported under another name, as `const handler = getUsers` or an imported function.
+        let local = ast
+            .find_export_statement()
+            .and_then(|es| es.get_value(name).cloned());
+        if let Some(symbol) = local.and_then(|l| self.resolve_binding(path, &ast, &l, visited)) {
+            return Some(symbol);
+        }
+
+        for re in ast.re_exports() {
+            let target = match &re.names {
+                None => name,
+                Some(names) => match names.iter().find(|p| p.key == name) {
+                    Some(prop) => prop.value.as_deref()?,
+                    None => continue,
+                },
+            };
+
+            let Some(full_path) = self.followable(path, &re.file) else {
+                continue;
+            };
+            if let Some(symbol) = self.resolve_export(&full_path, target, visited) {
+                return Some(symbol);
+            }
+        }
+
+        if self.options.directory_namespaces {
+            for sibling in index_siblings(path) {
+                if !self.is_followable(&sibling) {
+                    continue;
+                }
+                if let Some(symbol) = self.resolve_export(&sibling, name, visited) {
+                    return Some(symbol);
+                }
+            }
+        }
+
+        None
+    }
+
+    // the function a top level name of a module refers to, following `const a = b` and
+    // `const a = ns.b` aliases and the requires that bind them.
+    fn resolve_binding(
+        &mut self,
+        path: &str,
+        ast: &ASTNode,
+        name: &str,
+        visited: &mut HashSet<(String, String)>,
+    ) -> Option<Symbol> {
+        let mut name = name.to_string();
+        let mut aliases = HashSet::new();
+        while aliases.insert(name.clone()) {
+            if let Some(func) = ast.find_function(&name) {
+                return Some(Symbol {
+                    node: func.clone(),
+                    file_path: path.to_string(),
+                });
+            }
+
+            // `exports.x = lib.y`, the member of a module the file requires.
+            if let Some((base, member)) = name.split_once('.') {
+                let (specifier, _) = ast.find_binding(base)?.try_get_require_file()?;
+                return self
+                    .followable(path, &specifier)
+                    .and_then(|p| self.resolve_export(&p, member, visited));
+            }
+
+            let binding = ast.find_binding(&name)?;
+            let (import_name, ns) = match binding.try_get_require_file() {
+                Some((specifier, ve)) => {
+                    let key = match ve.lhs.as_ref() {
+                        ASTNode::ObjectPattern(op) => op
+                            .properties
+                            .iter()
+                            .find(|p| p.value.as_deref() == Some(&name))
+                            .map(|p| p.key.clone())?,
+                        // the whole module, a function only when it's assigned as module.exports.
+                        _ => String::from("default"),
+                    };
+                    (key, specifier)
+                }
+                None => {
+                    let ASTNode::VariableExpression(ve) = binding else {
+                        return None;
+                    };
+                    match ve.rhs.as_ref() {
+                        ASTNode::Identifier(ident) => {
+                            name = ident.name.clone();
+                            continue;
+                        }
+                        ASTNode::MemberExpression(me) => match me.base.as_ref() {
+                            ASTNode::Identifier(base) => {
+                                let (specifier, _) =
+                                    ast.find_binding(&base.name)?.try_get_require_file()?;
+                                (me.property.clone(), specifier)
+                            }
+                            _ => return None,
+                        },
+                        _ => return None,
+                    }
+                }
+            };
 
+            return self
+                .followable(path, &ns)
+                .and_then(|p| self.resolve_export(&p, &import_name, visited));
+        }
+
+        None
+    }
+
+    fn index_export(&mut self, required_file: &str, lhs: &ASTNode) {
         match lhs {
             ASTNode::ObjectPattern(op) => {
                 for prop in &op.properties {
-                    if let Some(func) = file.ast.find_exported_func(&prop.key) {
-                        self.scope.insert_symbol(
-                            &prop.value,
-                            Symbol {
-                                node: func.clone(),
-                                file_path: file.path.clone(),
-                            },
-                        )
+                    let Some(value) = &prop.value else { continue };
+                    let mut visited = HashSet::new();
+                    if let Some(symbol) =
+                        self.resolve_export(required_file, &prop.key, &mut visited)
+                    {
+                        self.scope.insert_symbol(value, symbol)
                     }
                 }
             }
             ASTNode::Identifier(ident) => {
-                if let Some(op) = file.ast.find_export_statement() {
-                    self.scope.insert_symbol(
-                        &ident.name,
-                        Symbol {
-                            node: ASTNode::ExportStatement(op.clone()),
-                            file_path: file.path.clone(),
-                        },
-                    )
+                // members re-exported by name from other files, found as `ident.member`.
+                let re_exported: Vec<String> = self
+                    .load_file(required_file)
+                    .ast
+                    .re_exports()
+                    .iter()
+                    .filter_map(|re| re.names.as_ref())
+                    .flatten()
+                    .map(|prop| prop.key.clone())
+                    .collect();
+                for member in re_exported {
+                    let mut visited = HashSet::new();
+                    if let Some(symbol) = self.resolve_export(required_file, &member, &mut visited)
+                    {
+                        self.scope
+                            .insert_symbol(&format!("{}.{}", ident.name, member), symbol);
+                    }
+                }
+
+                if let Some(symbol) = self.module_symbol(required_file) {
+                    self.scope.insert_symbol(&ident.name, symbol)
                 }
             }
-            _ => return,
+            _ => (),
+        }
+    }
+
+    // the exports of a required module as a whole, as bound by `const ns = require(...)`.
+    fn module_symbol(&mut self, required_file: &str) -> Option<Symbol> {
+        let export_file = self.resolve_export_file(required_file);
+        let file = self.files.get(&export_file).unwrap();
+        let node = match file.ast.find_export_statement() {
+            Some(op) => ASTNode::ExportStatement(op),
+            None => ASTNode::ExportAssignment(file.ast.find_assigned_export()?),
+        };
+
+        Some(Symbol {
+            node,
+            file_path: file.path.clone(),
+        })
+    }
+
+    // the module a namespace exports as `member`, as `users` of `api` in `api.users.fetch()`
+    // where api does `const users = require("./users")` and exports it.
+    fn resolve_namespace(
+        &mut self,
+        namespace: &Symbol,
+        member: &str,
+        visited: &mut HashSet<(String, String)>,
+    ) -> Option<Symbol> {
+        let ASTNode::ExportStatement(es) = &namespace.node else {
+            return None;
+        };
+        if !visited.insert((namespace.file_path.clone(), member.to_string())) {
+            return None;
+        }
+        let local = es.get_value(member)?;
+
+        let path = &namespace.file_path;
+        let ast = self.load_file(path).ast.clone();
+        let (specifier, ve) = ast.find_binding(local)?.try_get_require_file()?;
+        let required_file = self.followable(path, &specifier)?;
+
+        let module = self.module_symbol(&required_file)?;
+        match ve.lhs.as_ref() {
+            ASTNode::Identifier(_) => Some(module),
+            // `const { users } = require("./api")`, itself a namespace of that module.
+            ASTNode::ObjectPattern(op) => {
+                let key = op
+                    .properties
+                    .iter()
+                    .find(|p| p.value.as_ref() == Some(local))
+                    .map(|p| p.key.clone())?;
+                self.resolve_namespace(&module, &key, visited)
+            }
+            _ => None,
         }
     }
 
     fn index_block(&mut self, lines: &Vec<ASTNode>) {
         let current_file = self.scope.current().unwrap().file_path.clone();
         for node in lines {
-            if let Some((required_file, lhs)) = node.try_export_extract() {
-                if let Some(full_path) = utils::join_path(&current_file, &required_file) {
-                    self.index_export(&full_path, lhs);
+            let require = node
+                .try_export_extract()
+                .filter(|_| !self.options.skip_requires);
+            if let Some((required_file, lhs)) = require {
+                let candidates = utils::resolve_require_candidates(&current_file, &required_file)
+                    .unwrap_or_default();
+                if candidates.len() > 1 {
+                    self.warn_once(format!(
+                        "{}:{}: '{}' could be any of {}, using the first",
+                        current_file,
+                        node.get_start() + 1,
+                        required_file,
+                        candidates.join(", ")
+                    ));
+                }
+
+                match candidates.into_iter().next() {
+                    Some(full_path) if self.is_followable(&full_path) => {
+                        self.index_export(&full_path, lhs)
+                    }
+                    _ => (),
                 }
             }
 
-            match node {
-                ASTNode::FunctionStatement(fs) => self.scope.insert_symbol(
+            if let ASTNode::FunctionStatement(fs) = node {
+                self.scope.insert_symbol(
                     &fs.name,
                     Symbol {
                         node: ASTNode::FunctionStatement(fs.clone()),
                         file_path: current_file.clone(),
                     },
-                ),
-                _ => (),
+                )
+            }
+        }
+
+        // `const handler = getUsers` refers to whatever `getUsers` does, declared or imported.
+        for node in lines {
+            let ASTNode::VariableExpression(ve) = node else {
+                continue;
+            };
+            if let (ASTNode::Identifier(alias), ASTNode::Identifier(target)) =
+                (ve.lhs.as_ref(), ve.rhs.as_ref())
+            {
+                if let Some(symbol) = self.scope.find_symbol(&target.name).cloned() {
+                    self.scope.insert_symbol(&alias.name, symbol)
+                }
             }
         }
     }
@@ -215,7 +722,15 @@ impl ASTVisitor {
         self.index_block(lines);
 
         for node in lines {
+            if self.limit_reached() {
+                return;
+            }
+
             match node {
+                // runs as the block does, rather than only when called.
+                ASTNode::FunctionStatement(fs) if fs.kind == FunctionKind::Iife => {
+                    self.visit_node(node)
+                }
                 ASTNode::FunctionStatement(fs) => {
                     if fs.start > 0 {
                         self.grep(self.line_num, fs.start - 1);
@@ -232,7 +747,14 @@ impl ASTVisitor {
     }
 
     fn visit_function(&mut self, func_statement: &FunctionStatement) {
+        let file_path = self.scope.current().unwrap().file_path.clone();
+        let key = (file_path, func_statement.start);
+        if !self.active.insert(key.clone()) {
+            return;
+        }
+
         self.visit_node(&func_statement.body);
+        self.active.remove(&key);
     }
 
     fn visit_block_statement(&mut self, block_statement: &BlockStatement) {
@@ -241,40 +763,106 @@ impl ASTVisitor {
     }
 
     fn visit_call_expression(&mut self, call_expr: &CallExpression) {
-        let (base_name, call_name) = match call_expr.base.as_ref() {
-            ASTNode::Identifier(ident) => (&ident.name, &ident.name),
-            ASTNode::MemberExpression(me) => (&me.get_base().name, &me.property),
+        let (base_name, call_name, namespaces) = match call_expr.base.as_ref() {
+            ASTNode::Identifier(ident) => (&ident.name, &ident.name, vec![]),
+            ASTNode::MemberExpression(me) => match me.get_base() {
+                Some(base) => (&base.name, &me.property, me.get_namespaces()),
+                None => return,
+            },
             _ => return,
         };
 
-        let base_symbol = match self.scope.find_symbol(&base_name).map(|s| s.clone()) {
+        // `ns.member` re-exported from another file is indexed under its qualified name.
+        let qualified = match call_expr.base.as_ref() {
+            ASTNode::MemberExpression(_) if namespaces.is_empty() => {
+                format!("{}.{}", base_name, call_name)
+            }
+            _ => String::new(),
+        };
+        let mut base_symbol = match self
+            .scope
+            .find_symbol(&qualified)
+            .or_else(|| self.scope.find_symbol(base_name))
+            .cloned()
+        {
             Some(symbol) => symbol,
             None => return,
         };
+        // `a.b.c()` follows `b` from the module bound to `a` to the module `c` is called on.
+        for member in namespaces {
+            let mut visited = HashSet::new();
+            base_symbol = match self.resolve_namespace(&base_symbol, member, &mut visited) {
+                Some(symbol) => symbol,
+                None => return,
+            };
+        }
 
-        if base_symbol.file_path != self.scope.current().unwrap().file_path {
-            if !self.push_file_scope(&base_symbol.file_path) {
+        // `exports.x = lib.y`, called as `y` on the module the exporting file binds to `lib`.
+        let mut call_name = call_name.clone();
+        let mut visited = HashSet::new();
+        while let Some((symbol, member)) = self.resolve_member_export(&base_symbol, &call_name) {
+            if !visited.insert((symbol.file_path.clone(), member.clone())) {
                 return;
             }
+            base_symbol = symbol;
+            call_name = member;
         }
 
-        let func_name = match &base_symbol.node {
-            // TODO: handle nested member expressions e.g. foo.bar.baz()
-            ASTNode::ExportStatement(es) => match es.get_value(&call_name) {
-                Some(v) => v,
-                None => return,
-            },
-            ASTNode::FunctionStatement(fs) => &fs.name,
-            _ => return,
+        let entered_file = base_symbol.file_path != self.scope.current().unwrap().file_path;
+        if entered_file && !self.push_file_scope(&base_symbol.file_path) {
+            return;
+        }
+
+        for func_symbol in self.resolve_call(&base_symbol, &call_name) {
+            // a name the file imports or aliases from yet another file.
+            let current_file = &self.scope.current().unwrap().file_path;
+            let entered_func_file = func_symbol.file_path != *current_file
+                && self.push_file_scope(&func_symbol.file_path);
+
+            self.line_num = func_symbol.node.get_start();
+            self.visit_node(&func_symbol.node);
+            self.line_num = call_expr.start;
+
+            if entered_func_file {
+                self.scope.pop_file();
+            }
+        }
+
+        if entered_file {
+            self.scope.pop_file();
+        }
+    }
+
+    // the module and member a namespace's export refers to when exported as `lib.y`.
+    fn resolve_member_export(&mut self, module: &Symbol, name: &str) -> Option<(Symbol, String)> {
+        let ASTNode::ExportStatement(es) = &module.node else {
+            return None;
         };
+        let (base, member) = es.get_value(name)?.split_once('.')?;
+        let member = member.to_string();
+
+        let path = &module.file_path;
+        let ast = self.load_file(path).ast.clone();
+        let (specifier, ve) = ast.find_binding(base)?.try_get_require_file()?;
+        if !matches!(ve.lhs.as_ref(), ASTNode::Identifier(_)) {
+            return None;
+        }
+        let required_file = self.followable(path, &specifier)?;
 
-        let func_symbol = match self.scope.find_symbol(&func_name).map(|s| s.clone()) {
-            Some(symbol) => symbol,
-            None => return,
+        Some((self.module_symbol(&required_file)?, member))
+    }
+
+    fn resolve_call(&self, base_symbol: &Symbol, call_name: &str) -> Vec<Symbol> {
+        let func_names = match &base_symbol.node {
+            ASTNode::ExportStatement(es) => es.get_value(call_name).into_iter().collect(),
+            ASTNode::FunctionStatement(fs) => vec![&fs.name],
+            ASTNode::ExportAssignment(ea) => ea.names.iter().collect(),
+            _ => vec![],
         };
 
-        self.line_num = func_symbol.node.get_start();
-        self.visit_node(&func_symbol.node);
-        self.line_num = call_expr.start;
+        func_names
+            .into_iter()
+            .filter_map(|name| self.scope.find_symbol(name).cloned())
+            .collect()
     }
 }
@@ -1,8 +1,25 @@
-use std::process;
+use cjsgrep::{
+    analysis::{self, Analyzer, Conflict},
+    logger::Log,
+    parser::Parser,
+    utils,
+    visitor::{ASTVisitor, Options},
+};
+use std::{cell::RefCell, env, fs, process, rc::Rc, time};
 
 fn test_search(filename: &str, pattern: &str, func_start: Option<&str>, expected_out: Vec<&str>) {
+    test_search_with_args(filename, pattern, func_start, vec![], expected_out)
+}
+
+fn test_search_with_args(
+    filename: &str,
+    pattern: &str,
+    func_start: Option<&str>,
+    args: Vec<&str>,
+    expected_out: Vec<&str>,
+) {
     let mut cmd = process::Command::new("target/debug/cjsgrep");
-    cmd.arg(pattern).arg(filename);
+    cmd.arg(pattern).arg(filename).args(args);
 
     if let Some(func_start) = func_start {
         cmd.arg(format!("-n={}", func_start));
@@ -17,7 +34,62 @@ fn test_search(filename: &str, pattern: &str, func_start: Option<&str>, expected
 
     assert_eq!(lines.len(), expected_out.len());
     for (line, expected) in lines.iter().zip(expected_out.iter()) {
-        assert_eq!(line.contains(expected), true);
+        assert!(line.contains(expected));
+    }
+}
+
+fn run(args: Vec<&str>) -> process::Output {
+    process::Command::new("target/debug/cjsgrep")
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn strip_colours(s: &str) -> String {
+    let mut out = String::new();
+    let mut chars = s.chars();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' {
+            chars.by_ref().find(|c| *c == 'm');
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+/// Compares the output of cjsgrep against tests/golden/<name>.txt,
+/// set CJSGREP_UPDATE_GOLDEN=1 to write the current output instead.
+fn test_golden(name: &str, args: Vec<&str>) {
+    let out = run(args);
+    let actual = strip_colours(&String::from_utf8_lossy(&out.stdout));
+    let path = format!("tests/golden/{}.txt", name);
+
+    if env::var_os("CJSGREP_UPDATE_GOLDEN").is_some() {
+        fs::create_dir_all("tests/golden").unwrap();
+        fs::write(&path, &actual).unwrap();
+        return;
+    }
+
+    let expected = fs::read_to_string(&path)
+        .unwrap_or_else(|_| panic!("missing {}, run with CJSGREP_UPDATE_GOLDEN=1", path));
+    assert_eq!(actual, expected, "output differs from {}", path);
+}
+
+#[derive(Clone, Default)]
+struct Collect(Rc<RefCell<Vec<String>>>);
+
+impl Log for Collect {
+    fn err(&self, msg: &str) {
+        self.0.borrow_mut().push(format!("err: {}", msg));
+    }
+
+    fn warn(&self, msg: &str) {
+        self.0.borrow_mut().push(format!("warn: {}", msg));
+    }
+
+    fn info(&self, msg: &str) {
+        self.0.borrow_mut().push(format!("info: {}", msg));
     }
 }
 
@@ -74,4 +146,1581 @@ mod tests {
             vec!["obj = 1;", "obj = 2;", "obj = 3;"],
         );
     }
+
+    #[test]
+    fn member_chains() {
+        test_search(
+            "data/chain.js",
+            "pin",
+            Some("start"),
+            vec!["pin = \"fetch\";", "pin = \"remove\";", "pin = \"status\";"],
+        );
+        test_search("data/chain.js", "pin", Some("unknown"), vec![]);
+    }
+
+    #[test]
+    fn re_export() {
+        test_search(
+            "data/re-export.js",
+            "obj",
+            Some("foo"),
+            vec![
+                "function foo(obj) {",
+                "qux(obj);",
+                "function qux(obj) {",
+                "obj.qux = true;",
+            ],
+        );
+        test_search("data/re-export.js", "return", Some("foo"), vec!["return;"]);
+    }
+
+    #[test]
+    fn include_exclude() {
+        test_search_with_args(
+            "data/import-test.js",
+            "baz",
+            Some("foo"),
+            vec!["--exclude", "**/baz.js"],
+            vec!["bazz();"],
+        );
+        test_search_with_args(
+            "data/import-test.js",
+            "baz",
+            Some("foo"),
+            vec!["--include", "**/qux.js", "--include", "data/mixed/*"],
+            vec![
+                "bazz();",
+                "function baz(obj) {",
+                "obj.baz = 1;",
+                "function baz2(obj) {",
+                "obj.baz = 2;",
+            ],
+        );
+        test_search_with_args(
+            "data/import-test.js",
+            "baz",
+            Some("foo"),
+            vec![
+                "--include",
+                "data/mixed/*",
+                "--exclude",
+                "data/mixed/b?z.js",
+            ],
+            vec!["bazz();"],
+        );
+    }
+
+    #[test]
+    fn block_scoped_functions() {
+        test_search(
+            "data/block-scope.js",
+            "pin",
+            Some("foo"),
+            vec!["pin = \"block\";", "pin = \"module\";"],
+        );
+        test_search(
+            "data/block-scope.js",
+            "pin",
+            None,
+            vec!["pin = \"block\";", "pin = \"module\";"],
+        );
+    }
+
+    #[test]
+    fn default_params() {
+        test_search(
+            "data/default-params.js",
+            "pin",
+            Some("foo"),
+            vec!["pin = \"f\";", "pin = \"g\";"],
+        );
+    }
+
+    #[test]
+    fn subcommand_names_as_patterns() {
+        // the subcommands sit under `analyze`, so their names are still searched for.
+        test_search(
+            "data/subcommand-names.js",
+            "exports",
+            None,
+            vec!["function analyze(exports) {", "return exports.pin;"],
+        );
+        test_search(
+            "data/subcommand-names.js",
+            "summary",
+            None,
+            vec!["const summary = require", "analyze(summary);"],
+        );
+
+        let out = run(vec!["--", "analyze", "data/subcommand-names.js"]);
+        let stdout = strip_colours(&String::from_utf8_lossy(&out.stdout));
+        assert_eq!(
+            stdout,
+            "7: analyze(summary);\n3: function analyze(exports) {\n"
+        );
+    }
+
+    #[test]
+    fn resolve() {
+        let out = run(vec![
+            "analyze",
+            "resolve",
+            "data/import-test.js",
+            "./mixed/baz",
+        ]);
+        assert!(out.status.success());
+        assert!(String::from_utf8_lossy(&out.stdout)
+            .trim()
+            .ends_with("data/mixed/baz.js"));
+
+        let out = run(vec![
+            "analyze",
+            "resolve",
+            "data/import-test.js",
+            "./mixed/missing",
+        ]);
+        assert!(!out.status.success());
+        assert!(out.stdout.is_empty());
+
+        let out = run(vec!["analyze", "resolve", "data/import-test.js", "lodash"]);
+        assert!(!out.status.success());
+        assert!(String::from_utf8_lossy(&out.stderr).contains("external package"));
+    }
+
+    #[test]
+    fn encoding() {
+        test_search_with_args(
+            "data/encoding.js",
+            "café",
+            Some("foo"),
+            vec!["--encoding", "**/legacy-*.js=latin1"],
+            vec!["pin = \"café\";"],
+        );
+        test_search(
+            "data/encoding.js",
+            "café",
+            Some("foo"),
+            vec!["not valid utf-8"],
+        );
+
+        // subcommands read files with the same encodings.
+        let out = run(vec![
+            "analyze",
+            "show",
+            "data/mixed/legacy-latin1.js",
+            "greet",
+            "--encoding",
+            "latin1",
+        ]);
+        assert!(out.stderr.is_empty());
+        assert!(String::from_utf8_lossy(&out.stdout).contains("2:   pin = \"café\";"));
+
+        let out = run(vec!["analyze", "exports", "data/mixed/legacy-latin1.js"]);
+        assert!(String::from_utf8_lossy(&out.stdout).contains("not valid utf-8"));
+        let out = run(vec![
+            "analyze",
+            "exports",
+            "data/mixed/legacy-latin1.js",
+            "--encoding",
+            "latin1",
+        ]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "greet (declaration, line 1)\n"
+        );
+
+        // any WHATWG label, multibyte encodings included.
+        test_search_with_args(
+            "data/encoding-sjis.js",
+            "こんにちは",
+            Some("foo"),
+            vec!["--encoding", "**/legacy-sjis.js=shift_jis"],
+            vec!["pin = \"こんにちは\";"],
+        );
+        let out = run(vec!["pin", "data/encoding.js", "--encoding", "klingon"]);
+        assert!(!out.status.success());
+        assert!(String::from_utf8_lossy(&out.stderr).contains("unsupported encoding 'klingon'"));
+    }
+
+    #[test]
+    fn define_property_exports() {
+        test_search(
+            "data/define-property.js",
+            "pin",
+            Some("main"),
+            vec!["pin = \"foo\";", "pin = \"bar\";"],
+        );
+    }
+
+    #[test]
+    fn object_assign_exports() {
+        test_search(
+            "data/object-assign.js",
+            "pin",
+            Some("main"),
+            vec!["pin = \"foo\";", "pin = \"impl\";", "pin = \"baz\";"],
+        );
+
+        let out = run(vec!["analyze", "exports", "data/mixed/assign.js"]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "foo (declaration, line 1)\nbar (declaration, line 5)\nversion (data)\nbaz (declaration, line 9)\n"
+        );
+
+        // any other use of Object is a call like any other, and the calls in it are followed.
+        test_search(
+            "data/object-keys.js",
+            "pin",
+            Some("main"),
+            vec!["pin = \"load\";"],
+        );
+    }
+
+    #[test]
+    fn long_lines() {
+        let dir = env::temp_dir().join(format!("cjsgrep-long-line-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("long-line.js");
+        let body = "a(b, c); x.y.z = [1, 2]; if (q) { r = \"s\"; } ".repeat(25_000);
+        fs::write(&path, format!("function foo() {{ {} pin = 1; }}", body)).unwrap();
+        let path = path.display().to_string();
+
+        let started = time::Instant::now();
+        test_search_with_args(
+            &path,
+            "pin",
+            Some("foo"),
+            vec!["--max-line-length", "200"],
+            vec!["1: [omitted long line"],
+        );
+        assert!(started.elapsed() < time::Duration::from_secs(10));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn skip_requires() {
+        test_search_with_args(
+            "data/import-test.js",
+            "baz",
+            Some("foo"),
+            vec!["--skip-requires"],
+            vec!["bazz();"],
+        );
+    }
+
+    #[test]
+    fn switch_and_try_blocks() {
+        test_search(
+            "data/switch-try.js",
+            "pin",
+            Some("foo"),
+            vec![
+                "5: pin = \"case\";",
+                "12: pin = \"default\";",
+                "19: pin = \"try\";",
+                "24: pin = \"catch\";",
+                "29: pin = \"finally\";",
+            ],
+        );
+        test_search("data/switch-try.js", "pin", Some("bar"), vec![]);
+    }
+
+    #[test]
+    fn custom_logger() {
+        let logs = Collect::default();
+        let mut visitor = ASTVisitor::new("café").with_logger(Box::new(logs.clone()));
+        visitor.search("data/encoding.js", Some("foo")).unwrap();
+
+        let logs = logs.0.borrow();
+        assert_eq!(logs.len(), 1);
+        assert!(
+            logs[0].starts_with("warn: ") && logs[0].ends_with("not valid utf-8, decoding lossily")
+        );
+
+        let logs = Collect::default();
+        let mut visitor = ASTVisitor::new("pin").with_logger(Box::new(logs.clone()));
+        visitor.search("data/comments.js", Some("foo")).unwrap();
+        assert_eq!(*logs.0.borrow(), vec!["info: 2: pin = bar;"]);
+    }
+
+    #[test]
+    fn wrapped_arrow_functions() {
+        test_search(
+            "data/wrapped-arrow.js",
+            "pin",
+            Some("foo"),
+            vec![
+                "pin = \"handler\";",
+                "pin = \"wrapped\";",
+                "pin = \"direct\";",
+            ],
+        );
+        test_search(
+            "data/mixed/handlers.js",
+            "const",
+            Some("wrapped"),
+            vec!["6: const wrapped ="],
+        );
+    }
+
+    #[test]
+    fn list_functions() {
+        let out = run(vec!["analyze", "functions", "data/single-file.js"]);
+        assert!(out.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "1: abc (declaration)\n6: arrow (arrow)\n10: bar (arrow)\n14: foo (declaration)\n"
+        );
+
+        let out = run(vec![
+            "analyze",
+            "functions",
+            "data/single-file.js",
+            "--kind",
+            "arrow",
+        ]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "6: arrow (arrow)\n10: bar (arrow)\n"
+        );
+    }
+
+    #[test]
+    fn directory_requires() {
+        test_search(
+            "data/dot/consumer.js",
+            "pin",
+            Some("foo"),
+            vec!["pin = \"index\";"],
+        );
+        test_search(
+            "data/dot/nested/deep.js",
+            "pin",
+            Some("foo"),
+            vec!["pin = \"index\";"],
+        );
+
+        let out = run(vec!["analyze", "resolve", "data/dot/consumer.js", "./"]);
+        assert!(String::from_utf8_lossy(&out.stdout)
+            .trim()
+            .ends_with("data/dot/index.js"));
+    }
+
+    #[test]
+    fn export_assignment() {
+        test_search(
+            "data/single-export.js",
+            "pin",
+            Some("foo"),
+            vec!["pin = \"single\";"],
+        );
+        test_search(
+            "data/ts/consumer.ts",
+            "pin",
+            Some("foo"),
+            vec!["pin = \"greet\";"],
+        );
+    }
+
+    #[test]
+    fn module_extensions() {
+        test_search(
+            "data/modules/app.mts",
+            "pin",
+            Some("start"),
+            vec!["pin = \"greet\";", "pin = \"shout\";", "pin = \"parse\";"],
+        );
+        test_search(
+            "data/modules/app.cts",
+            "pin",
+            Some("start"),
+            vec!["pin = \"shout\";", "pin = \"parse\";"],
+        );
+
+        let out = run(vec!["analyze", "resolve", "data/modules/app.cts", "./esm"]);
+        assert!(String::from_utf8_lossy(&out.stdout)
+            .trim()
+            .ends_with("data/modules/esm.mts"));
+
+        let out = run(vec!["analyze", "orphans", "data/modules"]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "data/modules/app.cts\ndata/modules/app.mts\n"
+        );
+    }
+
+    #[test]
+    fn golden() {
+        test_golden(
+            "import-test",
+            vec!["baz", "data/import-test.js", "-n", "foo"],
+        );
+        test_golden("single-file", vec!["pin", "data/single-file.js"]);
+        test_golden(
+            "functions-handlers",
+            vec!["analyze", "functions", "data/mixed/handlers.js"],
+        );
+        test_golden(
+            "functions-nested",
+            vec!["analyze", "functions", "data/nested.js", "--nested"],
+        );
+    }
+
+    #[test]
+    fn wrapped_exports() {
+        test_search(
+            "data/wrapped-export.js",
+            "pin",
+            Some("foo"),
+            vec![
+                "pin = \"reducerA\";",
+                "pin = \"reducerB\";",
+                "pin = \"mapState\";",
+                "pin = \"Component\";",
+            ],
+        );
+
+        test_search(
+            "data/esm-default.js",
+            "pin",
+            Some("start"),
+            vec![
+                "pin = \"users\";",
+                "pin = \"posts\";",
+                "pin = \"mapState\";",
+                "pin = \"Profile\";",
+            ],
+        );
+    }
+
+    #[test]
+    fn default_imports() {
+        test_search(
+            "data/esm-default-fn.js",
+            "pin",
+            Some("start"),
+            vec![
+                "pin = \"greet\";",
+                "pin = \"arrow\";",
+                "pin = \"anonymous\";",
+            ],
+        );
+
+        let out = run(vec!["analyze", "def", "data/esm-default-fn.js", "greet"]);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let (header, body) = stdout.split_once('\n').unwrap();
+        assert!(header.ends_with("data/esm-default/greet.js"));
+        assert_eq!(
+            body,
+            "1: export default function greet() {\n2: pin = \"greet\";\n3: }\n"
+        );
+    }
+
+    #[test]
+    fn call_like_keywords() {
+        test_search(
+            "data/keywords.js",
+            "pin",
+            Some("foo"),
+            vec![
+                "pin = \"check\";",
+                "pin = \"pick\";",
+                "pin = \"start\";",
+                "pin = \"unless\";",
+                "pin = \"guard\";",
+                "pin = \"finish\";",
+            ],
+        );
+        test_search(
+            "data/keywords.js",
+            "pin",
+            Some("bar"),
+            vec!["pin = \"finish\";"],
+        );
+
+        // more can be added, here a macro's `unless (`, no longer a call to the helper.
+        test_search_with_args(
+            "data/keywords.js",
+            "pin",
+            Some("foo"),
+            vec!["--call-like", "unless"],
+            vec![
+                "pin = \"check\";",
+                "pin = \"pick\";",
+                "pin = \"start\";",
+                "pin = \"guard\";",
+                "pin = \"finish\";",
+            ],
+        );
+    }
+
+    #[test]
+    fn data_only_exports() {
+        test_search(
+            "data/data-export.js",
+            "pin",
+            Some("foo"),
+            vec!["pin = \"home\";"],
+        );
+
+        let out = run(vec!["analyze", "exports", "data/mixed/config.js"]);
+        assert!(out.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "routes (data)\nconfig (data)\nname (data)\nhome (declaration, line 8)\n"
+        );
+    }
+
+    #[test]
+    fn preserve_indentation() {
+        let trimmed = run(vec!["pin", "data/single-file.js", "-n", "foo"]);
+        let indented = run(vec![
+            "pin",
+            "data/single-file.js",
+            "-n",
+            "foo",
+            "--preserve-indentation",
+        ]);
+        let trimmed = strip_colours(&String::from_utf8_lossy(&trimmed.stdout));
+        let indented = strip_colours(&String::from_utf8_lossy(&indented.stdout));
+
+        assert_ne!(trimmed, indented);
+        assert_eq!(trimmed.lines().count(), indented.lines().count());
+        for (t, i) in trimmed.lines().zip(indented.lines()) {
+            let (t_line, t_content) = t.split_once(": ").unwrap();
+            let (i_line, i_content) = i.split_once(": ").unwrap();
+            assert_eq!(t_line, i_line);
+            assert_eq!(t_content, i_content.trim_start());
+        }
+        assert!(indented.contains("17:       let pin = a;"));
+    }
+
+    #[test]
+    fn member_exports() {
+        test_search(
+            "data/bracket-exports.js",
+            "pin",
+            Some("foo"),
+            vec![
+                "bracket-exports.js:11: skipping export with a computed key",
+                "pin = \"first\";",
+                "pin = \"second\";",
+                "pin = \"third\";",
+                "pin = \"fourth\";",
+            ],
+        );
+    }
+
+    #[test]
+    fn symlinked_packages() {
+        test_search(
+            "data/workspace/app/main.js",
+            "pin",
+            Some("foo"),
+            vec!["pin = \"main\";", "pin = \"greet\";", "pin = \"helper\";"],
+        );
+
+        let out = run(vec![
+            "analyze",
+            "resolve",
+            "data/workspace/app/main.js",
+            "@scope/pkg",
+        ]);
+        assert!(String::from_utf8_lossy(&out.stdout)
+            .trim()
+            .ends_with("data/workspace/packages/pkg/lib/index.js"));
+
+        // "exports" wins over "main", by its require condition and subpaths, and a package
+        // installed in node_modules rather than symlinked from the workspace isn't followed.
+        test_search(
+            "data/workspace/app/conditional.js",
+            "pin",
+            Some("foo"),
+            vec!["pin = \"conditional\";", "pin = \"util\";"],
+        );
+
+        let resolve = |specifier: &str| {
+            let out = run(vec![
+                "analyze",
+                "resolve",
+                "data/workspace/app/conditional.js",
+                specifier,
+            ]);
+            let stdout = String::from_utf8_lossy(&out.stdout).trim().to_string();
+            (stdout, String::from_utf8_lossy(&out.stderr).to_string())
+        };
+        assert!(resolve("@scope/conditional/util")
+            .0
+            .ends_with("data/workspace/packages/conditional/lib/util.js"));
+        let (stdout, stderr) = resolve("installed");
+        assert!(stdout.is_empty());
+        assert!(stderr.contains("'installed' is an external package installed in node_modules"));
+    }
+
+    #[test]
+    fn class_methods() {
+        let out = run(vec![
+            "analyze",
+            "functions",
+            "data/ts/service.ts",
+            "--kind",
+            "method",
+        ]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "7: Greeter.constructor (method)\n12: Greeter.greet (method)\n20: Greeter.size (method)\n"
+        );
+
+        test_search(
+            "data/ts/service.ts",
+            "pin",
+            Some("Greeter.constructor"),
+            vec!["8: pin = \"constructor\";"],
+        );
+        test_search(
+            "data/ts/service.ts",
+            "pin",
+            Some("Greeter.handle"),
+            vec!["17: pin = \"handle\";"],
+        );
+
+        let out = run(vec!["analyze", "functions", "data/class-keys.js"]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "2: Keys[\"quoted\"] (method)\n6: Keys.after (method)\n10: Keys[Symbol.iterator] (method)\n14: Keys[\"computed\"] (method)\n18: Keys.#secret (method)\n24: Keys.items (method)\n28: Keys.pages (method)\n36: Keys[42] (method)\n"
+        );
+        // a key computed from an expression is skipped with a warning rather than named.
+        let out = run(vec!["pin", "data/class-keys.js", "-n", "Keys[42]"]);
+        let stdout = strip_colours(&String::from_utf8_lossy(&out.stdout));
+        assert!(stdout.contains("class-keys.js:32: skipping class member with a computed key\n"));
+        assert!(stdout.ends_with("\n37: pin = \"answer\";\n"));
+        test_search(
+            "data/class-keys.js",
+            "pin",
+            Some("Keys.#secret"),
+            vec![
+                "32: skipping class member with a computed key",
+                "19: pin = \"secret\";",
+            ],
+        );
+
+        let out = run(vec!["analyze", "functions", "data/ts/fields.ts"]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "2: Form.onClick (arrow)\n6: Form.onSubmit (arrow)\n10: Form.onReset (arrow)\n14: Form.validate (method)\n22: Form.greet (method)\n28: Form.close (method)\n"
+        );
+        test_search(
+            "data/ts/fields.ts",
+            "pin",
+            Some("Form.greet"),
+            vec!["23: pin = \"greet\";"],
+        );
+    }
+
+    #[test]
+    fn count() {
+        let out = run(vec!["baz", "data/import-test.js", "-n", "foo", "--count"]);
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "5\n");
+
+        let out = run(vec![
+            "baz",
+            "data/import-test.js",
+            "-n",
+            "foo",
+            "-c",
+            "--exclude",
+            "**/baz.js",
+        ]);
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "1\n");
+
+        let out = run(vec!["pin", "data/bracket-exports.js", "-n", "foo", "-c"]);
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "4\n");
+        assert!(String::from_utf8_lossy(&out.stderr).contains("computed key"));
+    }
+
+    #[test]
+    fn dynamic_code() {
+        let out = run(vec!["analyze", "dynamic", "data/dynamic.js"]);
+        assert!(out.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "2: new Function\n6: eval\n9: eval\n"
+        );
+    }
+
+    #[test]
+    fn external_dependencies() {
+        let out = run(vec!["analyze", "deps", "data/deps/index.js"]);
+        assert!(out.status.success());
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let lines: Vec<&str> = stdout.lines().collect();
+        let expected = vec![
+            "@scope/ui",
+            "data/deps/index.js:2",
+            "data/deps/util.js:2",
+            "lodash",
+            "data/deps/index.js:1",
+            "data/deps/index.js:8",
+            "data/deps/util.js:1",
+            "react",
+            "data/deps/util.js:2",
+        ];
+        assert_eq!(lines.len(), expected.len());
+        for (line, expected) in lines.iter().zip(expected) {
+            assert!(
+                line.ends_with(expected),
+                "{} should end with {}",
+                line,
+                expected
+            );
+        }
+
+        let deps = Analyzer::new(Default::default())
+            .dependencies("data/deps/index.js")
+            .unwrap();
+        let mut names: Vec<&String> = deps.keys().collect();
+        names.sort();
+        assert_eq!(names, vec!["@scope/ui", "lodash", "react"]);
+        assert_eq!(deps["react"].len(), 1);
+        assert!(deps["react"][0].0.ends_with("data/deps/util.js"));
+        assert_eq!(deps["react"][0].1, 1);
+    }
+
+    #[test]
+    fn max_count() {
+        test_search_with_args(
+            "data/import-test.js",
+            "baz",
+            Some("foo"),
+            vec!["-m", "2"],
+            vec!["bazz();", "function baz(obj) {"],
+        );
+
+        let out = run(vec![
+            "baz",
+            "data/import-test.js",
+            "-n",
+            "foo",
+            "-c",
+            "-m",
+            "3",
+        ]);
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "3\n");
+    }
+
+    #[test]
+    fn json_requires() {
+        test_search(
+            "data/json-require.js",
+            "pin",
+            Some("foo"),
+            vec!["pin = settings.name;", "pin = \"bar\";"],
+        );
+
+        let mut visitor = ASTVisitor::new("pin").with_logger(Box::new(Collect::default()));
+        visitor.search("data/json-require.js", Some("foo")).unwrap();
+        assert_eq!(visitor.files_read().len(), 1);
+
+        let out = run(vec![
+            "analyze",
+            "resolve",
+            "data/json-require.js",
+            "./mixed/settings",
+        ]);
+        assert!(String::from_utf8_lossy(&out.stdout)
+            .trim()
+            .ends_with("data/mixed/settings.json"));
+    }
+
+    #[test]
+    fn function_byte_ranges() {
+        let src = fs::read_to_string("data/multibyte.js").unwrap();
+        let ast = Parser::new(&src).parse();
+        let source = |name: &str| {
+            let func = ast
+                .functions()
+                .into_iter()
+                .find(|f| f.name == name)
+                .unwrap();
+            &src[func.start_byte..func.end_byte]
+        };
+
+        assert_eq!(
+            source("greet"),
+            "function greet(name = \"José\") {\n  pin = \"¡hola \" + name + \"!\";\n}"
+        );
+        assert_eq!(
+            source("wave"),
+            "const wave = (emoji = \"👋\") => {\n  pin = emoji;\n}"
+        );
+        assert_eq!(source("après"), "function après() { pin = \"ü\"; }");
+
+        let out = run(vec!["analyze", "functions", "data/multibyte.js", "--bytes"]);
+        assert!(String::from_utf8_lossy(&out.stdout).contains(&format!(
+            "4: greet (declaration) {}..",
+            src.find("function greet").unwrap()
+        )));
+    }
+
+    #[test]
+    fn longest_functions() {
+        let out = run(vec!["analyze", "longest", "data/single-file.js", "-n", "3"]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "14: foo (declaration) 16 lines\n1: abc (declaration) 4 lines\n6: arrow (arrow) 3 lines\n"
+        );
+
+        let out = run(vec![
+            "analyze",
+            "longest",
+            "data/nested.js",
+            "--min-lines",
+            "5",
+        ]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "1: outer (declaration) 11 lines\n2: inner (declaration) 5 lines\n"
+        );
+
+        let source = Analyzer::new(Default::default())
+            .load("data/nested.js")
+            .unwrap();
+        let names: Vec<&str> = analysis::longest_functions(&source.ast, 5)
+            .iter()
+            .map(|f| f.name.as_str())
+            .collect();
+        assert_eq!(names, vec!["outer", "inner"]);
+    }
+
+    #[test]
+    fn complexity() {
+        let out = run(vec!["analyze", "complexity", "data/complexity.js"]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "1: straight (declaration) 1\n6: branches (declaration) 5\n15: handler (arrow) 6\n"
+        );
+
+        let out = run(vec![
+            "analyze",
+            "complexity",
+            "data/complexity.js",
+            "branches",
+        ]);
+        assert_eq!(String::from_utf8_lossy(&out.stdout), "5\n");
+
+        let out = run(vec![
+            "analyze",
+            "complexity",
+            "data/complexity.js",
+            "missing",
+        ]);
+        assert!(!out.status.success());
+
+        let analyzer = Analyzer::new(Default::default());
+        assert_eq!(
+            analyzer.complexity("data/complexity.js", "handler"),
+            Some(6)
+        );
+        assert_eq!(analyzer.complexity("data/complexity.js", "missing"), None);
+    }
+
+    #[test]
+    fn conditional_exports() {
+        test_search(
+            "data/conditional-export.js",
+            "pin",
+            Some("foo"),
+            vec![
+                "pin = \"dev\";",
+                "pin = \"prod\";",
+                "pin = \"block dev\";",
+                "pin = \"block prod\";",
+                "pin = \"ternary dev\";",
+                "pin = \"ternary prod\";",
+            ],
+        );
+
+        // re-exported as a default, every branch is still followed.
+        test_search(
+            "data/conditional-default.js",
+            "pin",
+            Some("start"),
+            vec!["pin = \"ternary dev\";", "pin = \"ternary prod\";"],
+        );
+
+        let out = run(vec![
+            "analyze",
+            "def",
+            "data/conditional-default.js",
+            "pick",
+        ]);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        assert!(stdout.contains("5: function prodImpl() {\n6: pin = \"ternary prod\";\n"));
+        assert!(stdout.contains("1: function devImpl() {"));
+    }
+
+    #[test]
+    fn show_function() {
+        let out = run(vec!["analyze", "show", "data/single-file.js", "bar"]);
+        assert!(out.status.success());
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "10: const bar = () => {\n11:   // pin bar\n12: };\n"
+        );
+
+        let out = run(vec!["analyze", "show", "data/single-file.js", "missing"]);
+        assert!(!out.status.success());
+        assert!(String::from_utf8_lossy(&out.stderr).contains("function missing not found"));
+
+        let lines: Vec<String> =
+            utils::read_lines("data/missing.js", Some(0..=3), Default::default()).collect();
+        assert!(lines.is_empty());
+        assert_eq!(
+            utils::read_lines("data/single-file.js", None, Default::default()).count(),
+            0
+        );
+    }
+
+    #[test]
+    fn definition() {
+        let out = run(vec!["analyze", "def", "data/single-file.js", "bar"]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "10: const bar = () => {\n11: // pin bar\n12: };\n"
+        );
+
+        let out = run(vec!["analyze", "def", "data/object-assign.js", "bar"]);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let (header, body) = stdout.split_once('\n').unwrap();
+        assert!(header.ends_with("data/mixed/assign.js"));
+        assert_eq!(body, "5: function impl() {\n6: pin = \"impl\";\n7: }\n");
+
+        let out = run(vec![
+            "analyze",
+            "def",
+            "data/chain.js",
+            "api.users.fetch",
+            "--preserve-indentation",
+        ]);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let (header, body) = stdout.split_once('\n').unwrap();
+        assert!(header.ends_with("data/chain/users.js"));
+        assert_eq!(body, "1: function fetch() {\n2:   pin = \"fetch\";\n3: }\n");
+
+        let out = run(vec!["analyze", "def", "data/chain.js", "api.users.missing"]);
+        assert!(!out.status.success());
+        assert!(
+            String::from_utf8_lossy(&out.stderr).contains("function api.users.missing not found")
+        );
+    }
+
+    #[test]
+    fn export_lists() {
+        test_search(
+            "data/esm-barrel.js",
+            "pin",
+            Some("start"),
+            vec![
+                "pin = \"foo\";",
+                "pin = \"bar\";",
+                "pin = \"baz\";",
+                "pin = \"starred\";",
+            ],
+        );
+        test_search(
+            "data/esm-barrel.js",
+            "pin",
+            Some("viaNamespace"),
+            vec!["pin = \"baz\";"],
+        );
+
+        let out = run(vec!["analyze", "exports", "data/esm/m.js"]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "foo (declaration, line 1)\nbar (declaration, line 5)\nbaz (declaration, line 9)\n"
+        );
+
+        let out = run(vec!["analyze", "exports", "data/esm/barrel.js"]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "foo (re-exported from ./m)\ndefault (re-exported from ./m)\nqux (re-exported from ./m)\n* (re-exported from ./star)\n"
+        );
+
+        let out = run(vec!["analyze", "exports", "data/esm-default/greet.js"]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "default (greet, declaration, line 1)\n"
+        );
+
+        let out = run(vec!["analyze", "exports", "data/esm-default/widget.js"]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "helper (declaration, line 1)\ndefault (Widget, class)\n"
+        );
+    }
+
+    #[test]
+    fn exported_imports() {
+        test_search(
+            "data/passthrough.js",
+            "pin",
+            Some("main"),
+            vec!["2: pin = \"a\";", "6: pin = \"b\";"],
+        );
+        test_search(
+            "data/passthrough.js",
+            "pin",
+            Some("viaNamespace"),
+            vec!["2: pin = \"a\";", "6: pin = \"b\";"],
+        );
+
+        let out = run(vec!["analyze", "exports", "data/passthrough/subset.js"]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "a (imported from ./x)\nb (imported from ./x)\n"
+        );
+
+        test_search(
+            "data/passthrough.js",
+            "pin",
+            Some("viaMembers"),
+            vec!["2: pin = \"a\";", "6: pin = \"b\";"],
+        );
+        let out = run(vec!["analyze", "exports", "data/passthrough/members.js"]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "first (imported from ./x)\nsecond (imported from ./x)\n"
+        );
+    }
+
+    #[test]
+    fn unreadable_files() {
+        let logger = Collect::default();
+        let read = utils::read_file("data/missing.js", Default::default(), &logger);
+        assert!(read.is_err());
+        assert!(logger.0.borrow()[0].starts_with("err: failed to read file data/missing.js"));
+
+        let out = run(vec!["pin", "data/missing.js"]);
+        assert_eq!(out.status.code(), Some(1));
+        assert!(String::from_utf8_lossy(&out.stderr).contains("file data/missing.js not found"));
+    }
+
+    #[test]
+    fn timings() {
+        let mut visitor = ASTVisitor::with_options(
+            "baz",
+            Options {
+                timings: true,
+                ..Options::default()
+            },
+        )
+        .with_logger(Box::new(Collect::default()));
+        visitor.search("data/import-test.js", Some("foo")).unwrap();
+        let mut timed: Vec<String> = visitor.timings().into_iter().map(|t| t.path).collect();
+        timed.sort();
+        assert_eq!(timed, visitor.files_read());
+
+        let mut visitor = ASTVisitor::new("baz").with_logger(Box::new(Collect::default()));
+        visitor.search("data/import-test.js", Some("foo")).unwrap();
+        assert!(visitor.timings().is_empty());
+
+        let out = run(vec!["baz", "data/import-test.js", "-n", "foo", "--timings"]);
+        let stderr = String::from_utf8_lossy(&out.stderr);
+        assert_eq!(stderr.lines().count(), 2);
+        assert!(stderr.contains("data/import-test.js: read "));
+    }
+
+    #[test]
+    fn ambiguous_extensions() {
+        test_search(
+            "data/ambiguous/main.js",
+            "pin",
+            Some("foo"),
+            vec!["main.js:1: './util' could be any of", "pin = \"js\";"],
+        );
+
+        let out = run(vec![
+            "analyze",
+            "resolve",
+            "data/ambiguous/main.js",
+            "./util",
+        ]);
+        assert!(String::from_utf8_lossy(&out.stdout)
+            .trim()
+            .ends_with("data/ambiguous/util.js"));
+        assert!(String::from_utf8_lossy(&out.stderr).contains("util.ts"));
+    }
+
+    #[test]
+    fn orphans() {
+        let out = run(vec!["analyze", "orphans", "data/orphans"]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "data/orphans/index.js\ndata/orphans/lib/a.test.js\ndata/orphans/lib/unused.js\n"
+        );
+
+        let out = run(vec![
+            "analyze",
+            "orphans",
+            "data/orphans",
+            "--exclude",
+            "**/index.js",
+            "--exclude",
+            "**/*.test.js",
+        ]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "data/orphans/lib/unused.js\n"
+        );
+
+        let filter = utils::PathFilter::new(vec![], vec![String::from("**/*.test.js")]);
+        let orphans = Analyzer::new(Default::default()).orphan_files("data/orphans", &filter);
+        let paths: Vec<&str> = orphans.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(
+            paths,
+            vec!["data/orphans/index.js", "data/orphans/lib/unused.js"]
+        );
+        assert!(orphans[1].absolute.ends_with("data/orphans/lib/unused.js"));
+    }
+
+    #[test]
+    fn function_names() {
+        // `load` is declared in both app.js and cli.js.
+        let out = run(vec!["analyze", "names", "data/rename"]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "format\nload\nparse\nrender\nrun\n"
+        );
+
+        let names = Analyzer::new(Default::default()).all_function_names("data/rename");
+        assert_eq!(
+            names.into_iter().collect::<Vec<_>>(),
+            ["format", "load", "parse", "render", "run"]
+        );
+    }
+
+    #[test]
+    fn invoked_functions() {
+        let out = run(vec![
+            "analyze",
+            "functions",
+            "data/iife.js",
+            "--kind",
+            "iife",
+        ]);
+        assert_eq!(
+            String::from_utf8_lossy(&out.stdout),
+            "1: ready (iife)\n6: legacy (iife)\n"
+        );
+
+        test_search(
+            "data/iife.js",
+            "pin",
+            None,
+            vec![
+                "pin = \"ready\";",
+                "pin = \"connect\";",
+                "pin = \"legacy\";",
+            ],
+        );
+        test_search("data/iife.js", "pin", Some("foo"), vec!["pin = \"foo\";"]);
+    }
+
+    #[test]
+    fn redundant_segments() {
+        let out = run(vec![
+            "analyze",
+            "resolve",
+            "data/import-test.js",
+            "./missing/../mixed/./baz",
+        ]);
+        assert!(out.status.success());
+        assert!(String::from_utf8_lossy(&out.stdout)
+            .trim()
+            .ends_with("data/mixed/baz.js"));
+
+        test_search(
+            "data/redundant-segments.js",
+            "obj.baz",
+            Some("main"),
+            vec!["obj.baz = 1;", "obj.baz = 2;"],
+        );
+    }
+
+    #[test]
+    fn routes() {
+        let out = run(vec![
+            "analyze",
+            "routes",
+            "data/routes/app.js",
+            "data/routes/users.js",
+        ]);
+        assert!(out.status.success());
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "data/routes/app.js:15: GET /users getUsers",
+                "data/routes/app.js:16: POST /users createUser",
+                "data/routes/app.js:17: DELETE /users/:id users.remove",
+                "data/routes/app.js:18: PUT /users/:id users.update",
+            ]
+        );
+
+        let out = run(vec![
+            "analyze",
+            "routes",
+            "data/routes/app.js",
+            "--methods",
+            "use,post",
+            "--handler-arg",
+            "1",
+        ]);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(
+            lines,
+            vec![
+                "data/routes/app.js:16: POST /users auth",
+                "data/routes/app.js:25: USE /admin adminRouter",
+            ]
+        );
+    }
+
+    #[test]
+    fn rename_check() {
+        let check = |old: &str, new: &str| {
+            let out = run(vec![
+                "analyze",
+                "rename-check",
+                "data/rename/lib.js",
+                old,
+                new,
+                "--importers",
+                "data/rename",
+            ]);
+            (
+                out.status.success(),
+                String::from_utf8_lossy(&out.stdout).into_owned(),
+            )
+        };
+
+        let (ok, stdout) = check("parse", "render");
+        assert!(!ok);
+        assert_eq!(
+            stdout.trim(),
+            "data/rename/lib.js:7: 'render' is already declared"
+        );
+
+        let (ok, stdout) = check("parse", "format");
+        assert!(!ok);
+        assert_eq!(
+            stdout.trim(),
+            "data/rename/lib.js:1: 'format' is already declared"
+        );
+
+        let (ok, stdout) = check("parse", "load");
+        assert!(!ok);
+        assert_eq!(
+            stdout.trim(),
+            "data/rename/app.js:3: 'load' is already declared where 'parse' is imported from data/rename/lib.js"
+        );
+
+        let (ok, stdout) = check("parse", "read");
+        assert!(ok);
+        assert!(stdout.is_empty());
+
+        let conflicts = Analyzer::new(Default::default())
+            .rename_conflicts("data/rename/lib.js", "parse", "load", Some("data/rename"))
+            .unwrap();
+        assert_eq!(
+            conflicts,
+            vec![Conflict {
+                path: String::from("data/rename/app.js"),
+                line: 2,
+                importer: true,
+            }]
+        );
+    }
+
+    #[test]
+    fn exported_aliases() {
+        test_search(
+            "data/alias.js",
+            "pin",
+            Some("start"),
+            vec!["pin = \"list\";", "pin = \"remove\";", "pin = \"local\";"],
+        );
+        test_search(
+            "data/alias.js",
+            "obj.baz",
+            Some("start"),
+            vec!["obj.baz = 1;", "obj.baz = 2;"],
+        );
+        test_search(
+            "data/alias.js",
+            "obj.baz",
+            Some("viaNamespace"),
+            vec!["obj.baz = 1;"],
+        );
+    }
+
+    #[test]
+    fn watch_missing_file() {
+        let dir = env::temp_dir().join(format!("cjsgrep-watch-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let main = dir.join("main.js");
+
+        // keeps watching after failing to read the starting file, and searches once it's there.
+        let mut child = process::Command::new("target/debug/cjsgrep")
+            .args(["pin", &main.display().to_string(), "--watch"])
+            .stdout(process::Stdio::piped())
+            .stderr(process::Stdio::piped())
+            .spawn()
+            .unwrap();
+        std::thread::sleep(time::Duration::from_millis(1000));
+        fs::write(&main, "function main() {\n  pin = 1;\n}\n\nmain();\n").unwrap();
+        std::thread::sleep(time::Duration::from_millis(1500));
+        child.kill().unwrap();
+        let out = child.wait_with_output().unwrap();
+
+        let stderr = strip_colours(&String::from_utf8_lossy(&out.stderr));
+        assert!(stderr.contains("not found"));
+        let stdout = strip_colours(&String::from_utf8_lossy(&out.stdout));
+        assert!(stdout.contains("searching again"));
+        assert!(stdout.contains("2: pin = 1;"));
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn changed_since() {
+        let dir = env::temp_dir().join(format!("cjsgrep-changed-{}", process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("main.js"),
+            "const { helper } = require(\"./helper\");\n\nfunction main() {\n  pin = 1;\n  helper();\n}\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("helper.js"),
+            "function helper() {\n  pin = 2;\n}\n\nmodule.exports = { helper };\n",
+        )
+        .unwrap();
+
+        let git = |args: &[&str]| {
+            let out = process::Command::new("git")
+                .arg("-C")
+                .arg(&dir)
+                .args(["-c", "user.name=t", "-c", "user.email=t@t"])
+                .args(args)
+                .output()
+                .unwrap();
+            assert!(
+                out.status.success(),
+                "{}",
+                String::from_utf8_lossy(&out.stderr)
+            );
+        };
+        let main = dir.join("main.js").display().to_string();
+        let search = || {
+            let out = run(vec!["pin", &main, "-n", "main", "--changed-since", "HEAD"]);
+            let stdout = strip_colours(&String::from_utf8_lossy(&out.stdout));
+            let lines: Vec<String> = stdout.lines().map(String::from).collect();
+            (out, lines)
+        };
+
+        // not yet a repository.
+        let (out, _) = search();
+        assert!(!out.status.success());
+        assert!(String::from_utf8_lossy(&out.stderr).contains("is not inside a git repository"));
+
+        git(&["init", "-q"]);
+        git(&["add", "."]);
+        git(&["commit", "-q", "-m", "initial"]);
+        let (out, lines) = search();
+        assert!(out.status.success());
+        assert!(lines.is_empty());
+
+        fs::write(
+            dir.join("helper.js"),
+            "function helper() {\n  pin = 3;\n}\n\nmodule.exports = { helper };\n",
+        )
+        .unwrap();
+        let (_, lines) = search();
+        assert_eq!(lines, vec!["2: pin = 3;"]);
+
+        // a file not yet added counts as changed.
+        fs::write(
+            dir.join("helper.js"),
+            "const { extra } = require(\"./extra\");\n\nfunction helper() {\n  pin = 3;\n  extra();\n}\n\nmodule.exports = { helper };\n",
+        )
+        .unwrap();
+        fs::write(
+            dir.join("extra.js"),
+            "function extra() {\n  pin = 4;\n}\n\nmodule.exports = { extra };\n",
+        )
+        .unwrap();
+        let (_, lines) = search();
+        assert_eq!(lines, vec!["4: pin = 3;", "2: pin = 4;"]);
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn exports_before_declarations() {
+        test_search(
+            "data/hoisted.js",
+            "pin",
+            Some("main"),
+            vec!["pin = \"early\";", "pin = \"late\";"],
+        );
+
+        let out = run(vec!["analyze", "exports", "data/hoisted/lib.js"]);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        assert!(stdout.contains("early (declaration, line 4)"));
+        assert!(stdout.contains("renamed (declaration, line 8)"));
+    }
+
+    #[test]
+    fn summary() {
+        let out = run(vec![
+            "analyze",
+            "summary",
+            "data/rename/lib.js",
+            "--importers",
+            "data/rename",
+        ]);
+        assert!(out.status.success());
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        let lines: Vec<&str> = stdout.lines().collect();
+        assert_eq!(
+            lines[0],
+            "data/rename/lib.js: 11 lines, 2 functions, 1 imports, 2 exports"
+        );
+        assert!(lines.contains(&"  3: parse (declaration) 3 lines"));
+        assert!(lines
+            .iter()
+            .any(|l| l.starts_with("  1: ./format -> ") && l.ends_with("data/rename/format.js")));
+        assert!(lines.contains(&"  render (declaration, line 7)"));
+        assert!(lines.ends_with(&[
+            "importers:",
+            "  data/rename/app.js:1",
+            "  data/rename/cli.js:1"
+        ]));
+
+        let out = run(vec!["analyze", "summary", "data/deps/index.js"]);
+        let stdout = String::from_utf8_lossy(&out.stdout);
+        assert!(stdout.contains("  4: fs -> node builtin"));
+        assert!(stdout.contains("  1: lodash -> 'lodash' is an external package"));
+        assert!(!stdout.contains("importers:"));
+
+        let logger = Collect::default();
+        let analyzer = Analyzer::new(Default::default()).with_logger(Box::new(logger.clone()));
+        let summary = analyzer
+            .summary_for_file("data/rename/lib.js", Some("data/rename"))
+            .unwrap();
+        assert_eq!(summary.lines, 11);
+        assert_eq!(summary.functions.len(), 2);
+        assert_eq!(summary.exports.len(), 2);
+        assert_eq!(
+            summary.importers,
+            Some(vec![
+                (String::from("data/rename/app.js"), 0),
+                (String::from("data/rename/cli.js"), 0)
+            ])
+        );
+        assert!(analyzer.summary_for_file("data/missing.js", None).is_none());
+        assert!(logger.0.borrow()[0].starts_with("err: failed to read file data/missing.js"));
+    }
+
+    #[test]
+    fn garbage_input() {
+        const FRAGMENTS: &[&str] = &[
+            "function", "const", "let", "class", "export", "import", "from", "module", "exports",
+            "require", "async", "static", "if", "=>", "a", "b", "(", ")", "{", "}", "[", "]", "=",
+            ".", ",", ":", ";", "?", "*", "/", "//", "/*", "*/", "\"", "'", "`", "\"x\"", "#", "@",
+            "1", "é", "👋", "\n", " ", "\\",
+        ];
+
+        let dir = env::temp_dir().join(format!("cjsgrep-garbage-{}", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("garbage.js").display().to_string();
+
+        let (done, finished) = std::sync::mpsc::channel();
+        std::thread::spawn(move || {
+            // a fixed seed so that failures reproduce.
+            let mut seed: u64 = 0x2545f4914f6cdd1d;
+            let mut next = || {
+                seed ^= seed << 13;
+                seed ^= seed >> 7;
+                seed ^= seed << 17;
+                seed as usize
+            };
+
+            for i in 0..2000 {
+                let len = next() % 64;
+                let src: String = (0..len)
+                    .map(|_| FRAGMENTS[next() % FRAGMENTS.len()])
+                    .collect();
+
+                Parser::new(&src).parse().functions();
+                Parser::new(&src).import_sites();
+                Parser::new(&src).dynamic_code_sites();
+                Parser::new(&src).route_sites(&[String::from("get")], None);
+
+                if i % 20 == 0 {
+                    fs::write(&path, &src).unwrap();
+                    let mut visitor =
+                        ASTVisitor::new("a").with_logger(Box::new(Collect::default()));
+                    let _ = visitor.search(&path, None);
+                }
+            }
+            done.send(()).unwrap();
+        });
+
+        finished
+            .recv_timeout(time::Duration::from_secs(30))
+            .expect("parsing garbage panicked or hung");
+        fs::remove_dir_all(&dir).unwrap();
+
+        // `let x` without a semicolon or value doesn't swallow the call on the next line.
+        test_search("data/asi.js", "pin", Some("main"), vec!["pin = 1;"]);
+    }
+
+    #[test]
+    fn recursive_calls() {
+        test_search(
+            "data/recursive.js",
+            "pin",
+            Some("main"),
+            vec!["pin = \"countdown\";", "pin = \"even\";", "pin = \"odd\";"],
+        );
+    }
+
+    #[test]
+    fn directory_namespaces() {
+        test_search(
+            "data/widgets-app.js",
+            "pin",
+            Some("render"),
+            vec!["pin = \"button\";", "pin = \"slider\";"],
+        );
+        test_search_with_args(
+            "data/widgets-app.js",
+            "pin",
+            Some("render"),
+            vec!["--directory-namespaces"],
+            vec![
+                "pin = \"button\";",
+                "pin = \"slider\";",
+                "pin = \"dialog\";",
+            ],
+        );
+    }
 }